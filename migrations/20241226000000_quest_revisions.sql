@@ -0,0 +1,17 @@
+-- Quest revision history
+-- Stores a full snapshot and a field-level diff every time ingest
+-- observes a different configuration for a quest
+
+CREATE TABLE IF NOT EXISTS quest_revisions (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    quest_id VARCHAR(255) NOT NULL,
+    revision INT NOT NULL,
+    config_version INT NOT NULL,
+    snapshot JSON NOT NULL,
+    changes JSON NOT NULL,
+    observed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    FOREIGN KEY (quest_id) REFERENCES quests(id) ON DELETE CASCADE,
+    UNIQUE KEY unique_quest_revision (quest_id, revision),
+    INDEX idx_observed_at (observed_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
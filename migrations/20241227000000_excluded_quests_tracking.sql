@@ -0,0 +1,10 @@
+-- Track when excluded quests were first and last reported by Discord
+
+ALTER TABLE excluded_quests
+    ADD COLUMN first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD INDEX idx_last_seen_at (last_seen_at);
+
+UPDATE excluded_quests
+SET first_seen_at = COALESCE(excluded_at, first_seen_at),
+    last_seen_at = COALESCE(excluded_at, last_seen_at);
@@ -0,0 +1,17 @@
+-- Archive of raw upstream responses
+-- Identical payloads (same SHA-256 of the canonical JSON) share one row
+
+CREATE TABLE IF NOT EXISTS upstream_payloads (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    sha256 CHAR(64) NOT NULL,
+    http_status SMALLINT NOT NULL,
+    byte_size INT NOT NULL,
+    payload JSON NOT NULL,
+    fetch_count INT NOT NULL DEFAULT 1,
+    first_fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    last_fetched_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    ingested_at TIMESTAMP NULL,
+    
+    UNIQUE KEY unique_sha256 (sha256),
+    INDEX idx_last_fetched_at (last_fetched_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,17 @@
+-- Capture upstream fields we do not model yet
+-- quests.extra mirrors the Discord shape and is merged back on reconstruction;
+-- schema_drift records every unknown field path the first time it is observed
+
+ALTER TABLE quests
+    ADD COLUMN extra JSON NULL AFTER rewards_expire_at;
+
+CREATE TABLE IF NOT EXISTS schema_drift (
+    path VARCHAR(512) PRIMARY KEY,
+    example JSON,
+    example_quest_id VARCHAR(255),
+    occurrences BIGINT NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    INDEX idx_first_seen_at (first_seen_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,13 @@
+-- Quarantine for quests that could not be parsed or saved during ingest
+
+CREATE TABLE IF NOT EXISTS quest_ingest_failures (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    quest_id VARCHAR(255),
+    stage VARCHAR(50) NOT NULL,
+    error TEXT NOT NULL,
+    raw JSON NOT NULL,
+    failed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    
+    INDEX idx_quest_id (quest_id),
+    INDEX idx_failed_at (failed_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,24 @@
+-- Platforms a quest's rewards can be redeemed on (rewards_config.platforms)
+-- Previously only the first platform was stored, copied onto every reward row
+
+CREATE TABLE IF NOT EXISTS quest_platforms (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    quest_id VARCHAR(255) NOT NULL,
+    platform INT NOT NULL,
+    
+    FOREIGN KEY (quest_id) REFERENCES quests(id) ON DELETE CASCADE,
+    INDEX idx_platform (platform)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Keep what we know until the next ingest stores the full list
+INSERT INTO quest_platforms (quest_id, platform)
+SELECT DISTINCT quest_id, platform FROM quest_rewards;
+
+-- Quests without reward rows never stored a platform; treat them as
+-- cross-platform (0), which every platform filter matches
+INSERT INTO quest_platforms (quest_id, platform)
+SELECT q.id, 0
+FROM quests q
+WHERE NOT EXISTS (SELECT 1 FROM quest_rewards r WHERE r.quest_id = q.id);
+
+ALTER TABLE quest_rewards DROP COLUMN platform;
@@ -0,0 +1,5 @@
+-- Keys the reconstruction would emit but Discord did not send for a quest
+-- (JSON pointers), removed again on reconstruction so it matches the input
+
+ALTER TABLE quests
+    ADD COLUMN absent_fields JSON NULL AFTER extra;
@@ -0,0 +1,6 @@
+-- Task metadata beyond the target, e.g. the video of WATCH_VIDEO tasks
+-- Previously kept only in quests.extra
+
+ALTER TABLE quest_tasks
+    ADD COLUMN assets JSON NULL AFTER external_ids,
+    ADD COLUMN messages JSON NULL AFTER assets;
@@ -0,0 +1,7 @@
+-- Task schema each quest was received in: 1 (task_config), 2 (task_config_v2)
+-- or NULL when it had none. Everything stored so far was read as v2.
+
+ALTER TABLE quests
+    ADD COLUMN task_config_version INT NULL AFTER task_join_operator;
+
+UPDATE quests SET task_config_version = 2;
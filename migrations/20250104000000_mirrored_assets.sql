@@ -0,0 +1,14 @@
+-- Quest assets downloaded into the local content-addressed store
+-- Each upstream path is downloaded once; paths with identical content share one file
+
+CREATE TABLE IF NOT EXISTS mirrored_assets (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    source_path VARCHAR(512) NOT NULL,
+    sha256 CHAR(64) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    mirrored_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY unique_source_path (source_path),
+    INDEX idx_sha256 (sha256)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,4 @@
+-- When the full quest list a filtered variant was built from was written
+-- A variant built before a refresh but stored after it must still count as stale
+
+ALTER TABLE cache_store ADD COLUMN source_updated_at TIMESTAMP NULL;
@@ -0,0 +1,51 @@
+//! Local stand-in for Discord's quests endpoint.
+//!
+//! ```bash
+//! MOCK_MODE=429:30 cargo run --bin mock_discord
+//! DISCORD_API_URL=http://127.0.0.1:4000/api/v10/quests/@me cargo run
+//! ```
+
+use std::net::SocketAddr;
+
+use kythia_quest_api::mock_discord::{default_payload, MockDiscord, MockMode};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "kythia_quest_api=info,mock_discord=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let port: u16 = std::env::var("MOCK_PORT")
+        .unwrap_or_else(|_| "4000".to_string())
+        .parse()
+        .unwrap_or(4000);
+
+    let payload = match std::env::var("MOCK_FIXTURE") {
+        Ok(path) => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+        Err(_) => default_payload(),
+    };
+
+    let mode: MockMode = std::env::var("MOCK_MODE")
+        .unwrap_or_else(|_| "ok".to_string())
+        .parse()
+        .map_err(anyhow::Error::msg)?;
+
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], port)), payload).await?;
+    mock.set_mode(mode.clone());
+
+    tracing::info!("🎭 Mock Discord serving {} ({:?})", mock.quests_url(), mode);
+    tracing::info!(
+        "🎛️  Change mode with: curl -X PUT -d 429 http://{}/_mock/mode",
+        mock.addr()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    tracing::info!("🛑 Mock Discord stopped");
+
+    Ok(())
+}
@@ -1,23 +1,171 @@
-use std::env;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use crate::utils::{
+    asset_mirror::DEFAULT_ASSET_CDN_URL,
+    credentials::{read_secret_file, TokenSource},
+    discord::DEFAULT_DISCORD_API_URL,
+    secret::Secret,
+};
+
+/// Where quests are ingested from (`QUEST_SOURCE`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuestSourceConfig {
+    /// Discord's quests endpoint, using `DISCORD_TOKEN`
+    Discord,
+    /// Every `*.json` file in `QUEST_SOURCE_DIR`
+    Directory(PathBuf),
+    /// `/v1/quests` of another instance of this API at `MIRROR_URL`
+    Mirror(String),
+}
+
+/// How the quest cache is kept fresh (`REFRESH_MODE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshMode {
+    /// A background task refreshes on a schedule; requests only read the cache
+    Background,
+    /// Requests that find a stale cache refresh it before answering
+    OnDemand,
+}
+
+/// Service configuration; secrets are wrapped in [`Secret`] so printing a
+/// `Config` with `{:?}` never reveals them
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub discord_token: String,
-    pub database_url: String,
+    pub quest_source: QuestSourceConfig,
+    /// Only required by the Discord source; imports and other sources run without it
+    pub discord_token: Option<Secret>,
+    /// Secrets file the token was read from, re-read when the token is rejected
+    pub discord_token_file: Option<PathBuf>,
+    /// `DISCORD_TOKEN` came from the process environment rather than `.env`
+    pub discord_token_in_environment: bool,
+    /// Quests endpoint to fetch from; point it at a mock server for tests or demos
+    pub discord_api_url: String,
+    pub upstream_connect_timeout_seconds: u64,
+    pub upstream_timeout_seconds: u64,
+    pub upstream_max_retries: u32,
+    pub upstream_retry_backoff_ms: u64,
+    pub upstream_breaker_threshold: u32,
+    pub upstream_breaker_cooldown_seconds: u64,
+    /// Upper bound for the pause a rate-limited response asks for
+    pub upstream_max_rate_limit_pause_seconds: u64,
+    pub database_url: Secret,
     pub port: u16,
     pub cache_duration_minutes: u64,
+    /// Filtered quest lists kept in the cache at once
+    pub cache_max_variants: u64,
+    /// How long past expiry a cached response may still be served
+    pub max_staleness_minutes: u64,
+    pub refresh_mode: RefreshMode,
+    pub refresh_min_interval_seconds: u64,
+    pub refresh_jitter_percent: u64,
     pub quest_age_days: i64,
+    pub payload_archive_retention: u64,
+    /// Where quest assets are mirrored; `None` disables mirroring
+    pub asset_mirror_dir: Option<PathBuf>,
+    /// CDN that relative asset paths are downloaded from
+    pub asset_cdn_url: String,
+    /// Prefix of the absolute asset URLs in the resolved response format
+    pub asset_base_url: String,
+    /// Largest asset that will be mirrored
+    pub asset_max_bytes: u64,
+    /// First wait before a failed asset download is tried again
+    pub asset_retry_backoff_seconds: u64,
+    /// Add `mirrored_assets` URLs to each quest in the quest list
+    pub asset_rewrite_urls: bool,
+    pub admin_token: Option<Secret>,
 }
 
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
+        // Checked first: `.env` never overrides variables that are already set
+        let discord_token_in_environment = env::var_os("DISCORD_TOKEN").is_some();
         dotenvy::dotenv().ok();
 
-        let discord_token = env::var("DISCORD_TOKEN")
-            .map_err(|_| anyhow::anyhow!("DISCORD_TOKEN must be set in environment"))?;
+        let quest_source = match env::var("QUEST_SOURCE")
+            .unwrap_or_else(|_| "discord".to_string())
+            .trim()
+        {
+            "" | "discord" => QuestSourceConfig::Discord,
+            "directory" => QuestSourceConfig::Directory(
+                env::var("QUEST_SOURCE_DIR")
+                    .ok()
+                    .filter(|dir| !dir.trim().is_empty())
+                    .map(PathBuf::from)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("QUEST_SOURCE_DIR must be set when QUEST_SOURCE=directory")
+                    })?,
+            ),
+            "mirror" => QuestSourceConfig::Mirror(
+                env::var("MIRROR_URL")
+                    .ok()
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("MIRROR_URL must be set when QUEST_SOURCE=mirror")
+                    })?,
+            ),
+            other => anyhow::bail!(
+                "Unknown QUEST_SOURCE '{}' (expected discord, directory or mirror)",
+                other
+            ),
+        };
+
+        let discord_token_file = env::var("DISCORD_TOKEN_FILE")
+            .ok()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+
+        // Read like every other secret, so a trailing newline never reaches the header
+        let discord_token = secret_from_env("DISCORD_TOKEN")?;
+
+        let discord_api_url = env::var("DISCORD_API_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_DISCORD_API_URL.to_string());
+
+        let upstream_connect_timeout_seconds = env::var("UPSTREAM_CONNECT_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .unwrap_or(10);
+
+        let upstream_timeout_seconds = env::var("UPSTREAM_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30);
+
+        let upstream_max_retries = env::var("UPSTREAM_MAX_RETRIES")
+            .unwrap_or_else(|_| "2".to_string())
+            .parse()
+            .unwrap_or(2);
+
+        let upstream_retry_backoff_ms = env::var("UPSTREAM_RETRY_BACKOFF_MS")
+            .unwrap_or_else(|_| "500".to_string())
+            .parse()
+            .unwrap_or(500);
+
+        let upstream_breaker_threshold = env::var("UPSTREAM_BREAKER_THRESHOLD")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .unwrap_or(5);
+
+        let upstream_breaker_cooldown_seconds = env::var("UPSTREAM_BREAKER_COOLDOWN_SECONDS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300);
+
+        let upstream_max_rate_limit_pause_seconds =
+            env::var("UPSTREAM_MAX_RATE_LIMIT_PAUSE_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600);
 
-        let database_url = env::var("DATABASE_URL")
-            .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set in environment"))?;
+        let database_url = secret_from_env("DATABASE_URL")?.ok_or_else(|| {
+            anyhow::anyhow!("DATABASE_URL or DATABASE_URL_FILE must be set in environment")
+        })?;
 
         let port = env::var("PORT")
             .unwrap_or_else(|_| "3000".to_string())
@@ -29,29 +177,172 @@ impl Config {
             .parse()
             .unwrap_or(30);
 
+        let cache_max_variants = env::var("CACHE_MAX_VARIANTS")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .unwrap_or(100);
+
+        let max_staleness_minutes = env::var("MAX_STALENESS_MINUTES")
+            .unwrap_or_else(|_| "1440".to_string())
+            .parse()
+            .unwrap_or(1440);
+
+        let refresh_mode = match env::var("REFRESH_MODE")
+            .unwrap_or_else(|_| "background".to_string())
+            .trim()
+        {
+            "" | "background" => RefreshMode::Background,
+            "on_demand" => RefreshMode::OnDemand,
+            other => anyhow::bail!(
+                "Unknown REFRESH_MODE '{}' (expected background or on_demand)",
+                other
+            ),
+        };
+
+        let refresh_min_interval_seconds = env::var("REFRESH_MIN_INTERVAL_SECONDS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .unwrap_or(60);
+
+        let refresh_jitter_percent = env::var("REFRESH_JITTER_PERCENT")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<u64>()
+            .unwrap_or(10)
+            .min(100);
+
         let quest_age_days = env::var("QUEST_AGE_DAYS")
             .unwrap_or_else(|_| "30".to_string())
             .parse()
             .unwrap_or(30);
 
-        if discord_token.trim().is_empty() {
+        let payload_archive_retention = env::var("PAYLOAD_ARCHIVE_RETENTION")
+            .unwrap_or_else(|_| "100".to_string())
+            .parse()
+            .unwrap_or(100);
+
+        let asset_mirror_dir = env::var("ASSET_MIRROR_DIR")
+            .ok()
+            .filter(|dir| !dir.trim().is_empty())
+            .map(PathBuf::from);
+
+        let asset_cdn_url = env::var("ASSET_CDN_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_ASSET_CDN_URL.to_string());
+
+        let asset_base_url = env::var("ASSET_BASE_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| asset_cdn_url.clone());
+
+        let asset_max_bytes = env::var("ASSET_MAX_BYTES")
+            .unwrap_or_else(|_| "52428800".to_string())
+            .parse()
+            .unwrap_or(52_428_800);
+
+        let asset_retry_backoff_seconds = env::var("ASSET_RETRY_BACKOFF_SECONDS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300);
+
+        let asset_rewrite_urls = env::var("ASSET_REWRITE_URLS")
+            .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        let admin_token = secret_from_env("ADMIN_TOKEN")?.filter(|token| !token.is_empty());
+
+        if discord_token.as_ref().is_some_and(Secret::is_empty) {
             anyhow::bail!("DISCORD_TOKEN cannot be empty");
         }
 
-        if database_url.trim().is_empty() {
+        if database_url.is_empty() {
             anyhow::bail!("DATABASE_URL cannot be empty");
         }
 
         Ok(Self {
+            quest_source,
             discord_token,
+            discord_token_file,
+            discord_token_in_environment,
+            discord_api_url,
+            upstream_connect_timeout_seconds,
+            upstream_timeout_seconds,
+            upstream_max_retries,
+            upstream_retry_backoff_ms,
+            upstream_breaker_threshold,
+            upstream_breaker_cooldown_seconds,
+            upstream_max_rate_limit_pause_seconds,
             database_url,
             port,
             cache_duration_minutes,
+            cache_max_variants,
+            max_staleness_minutes,
+            refresh_mode,
+            refresh_min_interval_seconds,
+            refresh_jitter_percent,
             quest_age_days,
+            payload_archive_retention,
+            asset_mirror_dir,
+            asset_cdn_url,
+            asset_base_url,
+            asset_max_bytes,
+            asset_retry_backoff_seconds,
+            asset_rewrite_urls,
+            admin_token,
         })
     }
 
+    /// Discord token, failing when it was not configured
+    pub fn require_discord_token(&self) -> anyhow::Result<&Secret> {
+        self.discord_token
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("DISCORD_TOKEN must be set in environment"))
+    }
+
+    /// Where a replaced Discord token is looked for after the current one is rejected
+    pub fn discord_token_source(&self) -> TokenSource {
+        match &self.discord_token_file {
+            Some(path) => TokenSource::File(path.clone()),
+            None if self.discord_token_in_environment => TokenSource::Environment,
+            None => TokenSource::DotEnv,
+        }
+    }
+
+    pub fn upstream_connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.upstream_connect_timeout_seconds)
+    }
+
+    pub fn upstream_timeout(&self) -> Duration {
+        Duration::from_secs(self.upstream_timeout_seconds)
+    }
+
+    /// Base delay between background refreshes, before jitter
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(
+            (self.cache_duration_minutes * 60).max(self.refresh_min_interval_seconds),
+        )
+    }
+
     pub fn cache_duration_ms(&self) -> i64 {
         (self.cache_duration_minutes * 60 * 1000) as i64
     }
+
+    /// Whether the quest list links to mirrored assets
+    pub fn rewrites_asset_urls(&self) -> bool {
+        self.asset_rewrite_urls && self.asset_mirror_dir.is_some()
+    }
+
+    /// Age after which even a stale cached response is no longer served
+    pub fn max_cache_age_ms(&self) -> i64 {
+        ((self.cache_duration_minutes + self.max_staleness_minutes) * 60 * 1000) as i64
+    }
+}
+
+/// `<NAME>_FILE` when set (Docker/Kubernetes secrets), otherwise `<NAME>`,
+/// without surrounding whitespace either way
+fn secret_from_env(name: &str) -> anyhow::Result<Option<Secret>> {
+    match env::var(format!("{}_FILE", name)) {
+        Ok(path) if !path.trim().is_empty() => read_secret_file(Path::new(path.trim())).map(Some),
+        _ => Ok(env::var(name).ok().map(|value| Secret::new(value.trim()))),
+    }
 }
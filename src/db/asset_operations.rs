@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use sqlx::MySqlPool;
+
+use super::{models::MirroredAsset, quest_models::QuestAssets};
+use crate::utils::{asset_mirror::asset_fields, error::ApiError};
+
+/// Asset paths of the quests that expired within the last N days, without duplicates
+pub async fn get_quest_asset_paths(
+    pool: &MySqlPool,
+    age_days: i64,
+) -> Result<Vec<String>, ApiError> {
+    let assets: Vec<QuestAssets> = sqlx::query_as(
+        r#"
+        SELECT a.*
+        FROM quest_assets a
+        JOIN quests q ON q.id = a.quest_id
+        WHERE q.expires_at >= DATE_SUB(UTC_TIMESTAMP(), INTERVAL ? DAY)
+        ORDER BY a.id
+        "#,
+    )
+    .bind(age_days)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    let mut paths: Vec<String> = assets
+        .iter()
+        .flat_map(asset_fields)
+        .map(|(_, path)| path.to_string())
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    Ok(paths)
+}
+
+/// Every mirrored asset keyed by its upstream path
+pub async fn get_mirrored_assets(
+    pool: &MySqlPool,
+) -> Result<HashMap<String, MirroredAsset>, ApiError> {
+    let assets: Vec<MirroredAsset> =
+        sqlx::query_as("SELECT source_path, sha256, content_type, size_bytes FROM mirrored_assets")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(assets
+        .into_iter()
+        .map(|asset| (asset.source_path.clone(), asset))
+        .collect())
+}
+
+/// A mirrored asset by content hash
+pub async fn get_mirrored_asset(
+    pool: &MySqlPool,
+    sha256: &str,
+) -> Result<Option<MirroredAsset>, ApiError> {
+    sqlx::query_as(
+        r#"
+        SELECT source_path, sha256, content_type, size_bytes
+        FROM mirrored_assets
+        WHERE sha256 = ?
+        ORDER BY id
+        LIMIT 1
+        "#,
+    )
+    .bind(sha256)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))
+}
+
+/// Record a downloaded asset, replacing an earlier download of the same path
+pub async fn upsert_mirrored_asset(
+    pool: &MySqlPool,
+    asset: &MirroredAsset,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO mirrored_assets (source_path, sha256, content_type, size_bytes, mirrored_at)
+        VALUES (?, ?, ?, ?, UTC_TIMESTAMP())
+        ON DUPLICATE KEY UPDATE
+            sha256 = VALUES(sha256),
+            content_type = VALUES(content_type),
+            size_bytes = VALUES(size_bytes),
+            mirrored_at = UTC_TIMESTAMP()
+        "#,
+    )
+    .bind(&asset.source_path)
+    .bind(&asset.sha256)
+    .bind(&asset.content_type)
+    .bind(asset.size_bytes)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use sqlx::MySqlPool;
+
+use super::models::SchemaDrift;
+use crate::utils::error::ApiError;
+
+/// Record an observation of an unknown field path
+///
+/// Returns `true` when the path had never been seen before.
+pub async fn upsert_schema_drift(
+    pool: &MySqlPool,
+    path: &str,
+    example: &serde_json::Value,
+    quest_id: &str,
+) -> Result<bool, ApiError> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO schema_drift (
+            path, example, example_quest_id, occurrences, first_seen_at, last_seen_at
+        )
+        VALUES (?, ?, ?, 1, UTC_TIMESTAMP(), UTC_TIMESTAMP())
+        ON DUPLICATE KEY UPDATE
+            occurrences = occurrences + 1,
+            last_seen_at = UTC_TIMESTAMP()
+        "#,
+    )
+    .bind(path)
+    .bind(example)
+    .bind(quest_id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    // MySQL reports 1 affected row for an insert and 2 for an update
+    Ok(result.rows_affected() == 1)
+}
+
+/// List observed unknown field paths, newest first
+pub async fn get_schema_drift(pool: &MySqlPool) -> Result<Vec<SchemaDrift>, ApiError> {
+    let drift = sqlx::query_as::<_, SchemaDrift>(
+        "SELECT * FROM schema_drift ORDER BY first_seen_at DESC, path",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(drift)
+}
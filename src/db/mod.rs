@@ -1,4 +1,7 @@
+pub mod asset_operations;
+pub mod drift_operations;
 pub mod models;
 pub mod operations;
+pub mod payload_operations;
 pub mod quest_models;
 pub mod quest_operations;
@@ -7,5 +7,49 @@ pub struct CacheStore {
     pub id: String,
     pub data: serde_json::Value,
     pub updated_at: DateTime<Utc>,
+    /// For filtered variants, `updated_at` of the full list they were built from
+    pub source_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Archived upstream response (without the payload body)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UpstreamPayloadSummary {
+    pub id: i64,
+    pub sha256: String,
+    pub http_status: i16,
+    pub byte_size: i32,
+    pub fetch_count: i32,
+    pub first_fetched_at: DateTime<Utc>,
+    pub last_fetched_at: DateTime<Utc>,
+    pub ingested_at: Option<DateTime<Utc>>,
+}
+
+/// Archived upstream response including its payload
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UpstreamPayload {
+    pub id: i64,
+    pub sha256: String,
+    pub http_status: i16,
+    pub payload: serde_json::Value,
+}
+
+/// Upstream field path that is not part of our quest model
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SchemaDrift {
+    pub path: String,
+    pub example: Option<serde_json::Value>,
+    pub example_quest_id: Option<String>,
+    pub occurrences: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Quest asset stored in the local mirror, addressed by the SHA-256 of its content
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MirroredAsset {
+    pub source_path: String,
+    pub sha256: String,
+    pub content_type: String,
+    pub size_bytes: i64,
 }
 
@@ -6,7 +6,7 @@ use crate::utils::error::ApiError;
 
 pub async fn get_cache(pool: &MySqlPool, key: &str) -> Result<Option<CacheStore>, ApiError> {
     let cache = sqlx::query_as::<_, CacheStore>(
-        "SELECT id, data, updated_at FROM cache_store WHERE id = ?",
+        "SELECT id, data, updated_at, source_updated_at FROM cache_store WHERE id = ?",
     )
     .bind(key)
     .fetch_optional(pool)
@@ -39,6 +39,83 @@ pub async fn upsert_cache(
     Ok(())
 }
 
+/// Store a filtered variant, tagged with when the full list it was built from was written
+pub async fn upsert_cache_variant(
+    pool: &MySqlPool,
+    key: &str,
+    data: &serde_json::Value,
+    source_updated_at: DateTime<Utc>,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO cache_store (id, data, updated_at, source_updated_at)
+        VALUES (?, ?, UTC_TIMESTAMP(), ?)
+        ON DUPLICATE KEY UPDATE
+            data = VALUES(data),
+            updated_at = UTC_TIMESTAMP(),
+            source_updated_at = VALUES(source_updated_at)
+        "#,
+    )
+    .bind(key)
+    .bind(data)
+    .bind(source_updated_at)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Drop the filtered variants cached under `<key>?...`
+pub async fn delete_cache_variants(pool: &MySqlPool, key: &str) -> Result<u64, ApiError> {
+    let result = sqlx::query("DELETE FROM cache_store WHERE id LIKE ?")
+        .bind(variant_pattern(key))
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(result.rows_affected())
+}
+
+/// Keep only the `max` most recently built variants of `key`, returning how many were evicted
+pub async fn prune_cache_variants(pool: &MySqlPool, key: &str, max: u64) -> Result<u64, ApiError> {
+    let pattern = variant_pattern(key);
+
+    // MySQL cannot LIMIT a subquery of the table being deleted from, hence the derived table
+    let result = sqlx::query(
+        r#"
+        DELETE FROM cache_store
+        WHERE id LIKE ?
+          AND id NOT IN (
+              SELECT id FROM (
+                  SELECT id FROM cache_store
+                  WHERE id LIKE ?
+                  ORDER BY updated_at DESC, id
+                  LIMIT ?
+              ) AS newest
+          )
+        "#,
+    )
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(max)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(result.rows_affected())
+}
+
+/// LIKE pattern matching the variants of `key`; the key itself must not act as a pattern
+fn variant_pattern(key: &str) -> String {
+    format!(
+        "{}?%",
+        key.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
+
 pub fn is_cache_stale(updated_at: DateTime<Utc>, duration_ms: i64) -> bool {
     let now = Utc::now();
     let time_diff = now.signed_duration_since(updated_at);
@@ -0,0 +1,122 @@
+use chrono::{DateTime, Utc};
+use sqlx::MySqlPool;
+
+use super::models::{UpstreamPayload, UpstreamPayloadSummary};
+use crate::utils::error::ApiError;
+
+/// Most recently fetched payload hash and whether it was ingested successfully
+pub async fn get_latest_payload_hash(pool: &MySqlPool) -> Result<Option<(String, bool)>, ApiError> {
+    let row: Option<(String, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        SELECT sha256, ingested_at
+        FROM upstream_payloads
+        ORDER BY last_fetched_at DESC, id DESC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(row.map(|(sha256, ingested_at)| (sha256, ingested_at.is_some())))
+}
+
+/// Store a payload, or bump the fetch counters if the same content was seen before
+pub async fn upsert_payload(
+    pool: &MySqlPool,
+    sha256: &str,
+    http_status: u16,
+    byte_size: usize,
+    payload: &serde_json::Value,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO upstream_payloads (
+            sha256, http_status, byte_size, payload, fetch_count,
+            first_fetched_at, last_fetched_at
+        )
+        VALUES (?, ?, ?, ?, 1, UTC_TIMESTAMP(), UTC_TIMESTAMP())
+        ON DUPLICATE KEY UPDATE
+            http_status = VALUES(http_status),
+            fetch_count = fetch_count + 1,
+            last_fetched_at = UTC_TIMESTAMP()
+        "#,
+    )
+    .bind(sha256)
+    .bind(http_status)
+    .bind(byte_size as i64)
+    .bind(payload)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Record that a payload was fully ingested into the quest tables
+pub async fn mark_payload_ingested(pool: &MySqlPool, sha256: &str) -> Result<(), ApiError> {
+    sqlx::query("UPDATE upstream_payloads SET ingested_at = UTC_TIMESTAMP() WHERE sha256 = ?")
+        .bind(sha256)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Delete everything but the `keep` most recently fetched payloads
+pub async fn prune_payloads(pool: &MySqlPool, keep: u64) -> Result<u64, ApiError> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM upstream_payloads
+        WHERE id NOT IN (
+            SELECT id FROM (
+                SELECT id FROM upstream_payloads
+                ORDER BY last_fetched_at DESC, id DESC
+                LIMIT ?
+            ) AS kept
+        )
+        "#,
+    )
+    .bind(keep)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(result.rows_affected())
+}
+
+/// List archived payloads, newest first, without their bodies
+pub async fn list_payloads(
+    pool: &MySqlPool,
+    limit: u64,
+) -> Result<Vec<UpstreamPayloadSummary>, ApiError> {
+    let payloads = sqlx::query_as::<_, UpstreamPayloadSummary>(
+        r#"
+        SELECT id, sha256, http_status, byte_size, fetch_count,
+               first_fetched_at, last_fetched_at, ingested_at
+        FROM upstream_payloads
+        ORDER BY last_fetched_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(payloads)
+}
+
+/// Get a single archived payload with its body
+pub async fn get_payload(pool: &MySqlPool, id: i64) -> Result<Option<UpstreamPayload>, ApiError> {
+    let payload = sqlx::query_as::<_, UpstreamPayload>(
+        "SELECT id, sha256, http_status, payload FROM upstream_payloads WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(payload)
+}
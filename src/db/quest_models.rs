@@ -23,8 +23,15 @@ pub struct Quest {
     pub cta_link: Option<String>,
     pub cta_button_label: Option<String>,
     pub task_join_operator: String,
+    /// Task schema the quest was received in: 1 (`task_config`), 2
+    /// (`task_config_v2`) or `None` when it had no tasks
+    pub task_config_version: Option<i32>,
     pub reward_assignment_method: i32,
     pub rewards_expire_at: Option<DateTime<Utc>>,
+    /// Upstream fields we do not model, in the Discord shape
+    pub extra: Option<serde_json::Value>,
+    /// JSON pointers of keys Discord did not send, removed on reconstruction
+    pub absent_fields: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -55,6 +62,10 @@ pub struct QuestTask {
     pub target: i32,
     pub applications: Option<serde_json::Value>,
     pub external_ids: Option<serde_json::Value>,
+    /// Task media, e.g. the video of a WATCH_VIDEO task
+    pub assets: Option<serde_json::Value>,
+    /// Task texts, e.g. the video title
+    pub messages: Option<serde_json::Value>,
 }
 
 /// Quest rewards
@@ -68,7 +79,6 @@ pub struct QuestReward {
     pub reward_name_with_article: String,
     pub orb_quantity: Option<i32>,
     pub redemption_instructions: Option<serde_json::Value>,
-    pub platform: i32,
 }
 
 /// Quest feature flags
@@ -79,6 +89,14 @@ pub struct QuestFeature {
     pub feature_id: i32,
 }
 
+/// Platform a quest's rewards can be redeemed on
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuestPlatform {
+    pub id: i64,
+    pub quest_id: String,
+    pub platform: i32,
+}
+
 /// User quest progress and status
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct QuestUserStatus {
@@ -95,6 +113,43 @@ pub struct QuestUserStatus {
     pub last_updated: DateTime<Utc>,
 }
 
+/// Historical snapshot of a quest configuration
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuestRevision {
+    pub id: i64,
+    pub quest_id: String,
+    pub revision: i32,
+    pub config_version: i32,
+    pub snapshot: serde_json::Value,
+    pub changes: serde_json::Value,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Quest that Discord reports as excluded, optionally replaced by a successor
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExcludedQuest {
+    pub id: String,
+    pub replacement_id: Option<String>,
+    pub excluded_at: Option<DateTime<Utc>>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Name of the excluded quest, if we ever stored it
+    pub quest_name: Option<String>,
+    /// Name of the successor quest, if we have stored it
+    pub replacement_quest_name: Option<String>,
+}
+
+/// Quest quarantined because it could not be parsed or saved
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QuestIngestFailure {
+    pub id: i64,
+    pub quest_id: Option<String>,
+    pub stage: String,
+    pub error: String,
+    pub raw: serde_json::Value,
+    pub failed_at: DateTime<Utc>,
+}
+
 /// Complete quest data (for reconstruction)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompleteQuest {
@@ -103,5 +158,6 @@ pub struct CompleteQuest {
     pub tasks: Vec<QuestTask>,
     pub rewards: Vec<QuestReward>,
     pub features: Vec<QuestFeature>,
+    pub platforms: Vec<QuestPlatform>,
     pub user_statuses: Vec<QuestUserStatus>,
 }
@@ -1,21 +1,29 @@
 // use chrono::{DateTime, Utc};
 use serde_json::Value as JsonValue;
-use sqlx::MySqlPool;
+use sqlx::{
+    pool::PoolConnection, Acquire, MySql, MySqlConnection, MySqlPool, QueryBuilder, Transaction,
+};
 
 use super::quest_models::*;
-use crate::utils::error::ApiError;
+use crate::utils::{
+    error::ApiError,
+    platform::Platform,
+    quest_filter::{QuestFilter, QuestStatus},
+    reward_kind::KNOWN_REWARD_TYPES,
+};
 
 /// Insert or update a quest
-pub async fn upsert_quest(pool: &MySqlPool, quest: &Quest) -> Result<(), ApiError> {
+pub async fn upsert_quest(conn: &mut MySqlConnection, quest: &Quest) -> Result<(), ApiError> {
     sqlx::query(
         r#"
         INSERT INTO quests (
             id, config_version, starts_at, expires_at, application_id, application_name,
             application_link, share_policy, preview, primary_color, secondary_color,
             quest_name, game_title, game_publisher, cta_link, cta_button_label,
-            task_join_operator, reward_assignment_method, rewards_expire_at
+            task_join_operator, task_config_version, reward_assignment_method,
+            rewards_expire_at, extra, absent_fields
         )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ON DUPLICATE KEY UPDATE
             config_version = VALUES(config_version),
             starts_at = VALUES(starts_at),
@@ -33,8 +41,11 @@ pub async fn upsert_quest(pool: &MySqlPool, quest: &Quest) -> Result<(), ApiErro
             cta_link = VALUES(cta_link),
             cta_button_label = VALUES(cta_button_label),
             task_join_operator = VALUES(task_join_operator),
+            task_config_version = VALUES(task_config_version),
             reward_assignment_method = VALUES(reward_assignment_method),
             rewards_expire_at = VALUES(rewards_expire_at),
+            extra = VALUES(extra),
+            absent_fields = VALUES(absent_fields),
             updated_at = CURRENT_TIMESTAMP
         "#,
     )
@@ -55,9 +66,12 @@ pub async fn upsert_quest(pool: &MySqlPool, quest: &Quest) -> Result<(), ApiErro
     .bind(&quest.cta_link)
     .bind(&quest.cta_button_label)
     .bind(&quest.task_join_operator)
+    .bind(quest.task_config_version)
     .bind(quest.reward_assignment_method)
     .bind(quest.rewards_expire_at)
-    .execute(pool)
+    .bind(&quest.extra)
+    .bind(&quest.absent_fields)
+    .execute(&mut *conn)
     .await
     .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
@@ -66,18 +80,8 @@ pub async fn upsert_quest(pool: &MySqlPool, quest: &Quest) -> Result<(), ApiErro
 
 /// Insert or update quest assets
 pub async fn upsert_quest_assets(
-    pool: &MySqlPool,
-    quest_id: &str,
-    hero: Option<&str>,
-    hero_video: Option<&str>,
-    quest_bar_hero: Option<&str>,
-    quest_bar_hero_video: Option<&str>,
-    game_tile: Option<&str>,
-    logotype: Option<&str>,
-    game_tile_light: Option<&str>,
-    game_tile_dark: Option<&str>,
-    logotype_light: Option<&str>,
-    logotype_dark: Option<&str>,
+    conn: &mut MySqlConnection,
+    assets: &QuestAssets,
 ) -> Result<(), ApiError> {
     sqlx::query(
         r#"
@@ -100,18 +104,18 @@ pub async fn upsert_quest_assets(
             logotype_dark = VALUES(logotype_dark)
         "#,
     )
-    .bind(quest_id)
-    .bind(hero)
-    .bind(hero_video)
-    .bind(quest_bar_hero)
-    .bind(quest_bar_hero_video)
-    .bind(game_tile)
-    .bind(logotype)
-    .bind(game_tile_light)
-    .bind(game_tile_dark)
-    .bind(logotype_light)
-    .bind(logotype_dark)
-    .execute(pool)
+    .bind(&assets.quest_id)
+    .bind(&assets.hero)
+    .bind(&assets.hero_video)
+    .bind(&assets.quest_bar_hero)
+    .bind(&assets.quest_bar_hero_video)
+    .bind(&assets.game_tile)
+    .bind(&assets.logotype)
+    .bind(&assets.game_tile_light)
+    .bind(&assets.game_tile_dark)
+    .bind(&assets.logotype_light)
+    .bind(&assets.logotype_dark)
+    .execute(&mut *conn)
     .await
     .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
@@ -120,122 +124,150 @@ pub async fn upsert_quest_assets(
 
 /// Delete and re-insert quest tasks
 pub async fn replace_quest_tasks(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     quest_id: &str,
-    tasks: &[(String, i32, Option<JsonValue>, Option<JsonValue>)],
+    tasks: &[QuestTask],
 ) -> Result<(), ApiError> {
     // Delete existing tasks
     sqlx::query("DELETE FROM quest_tasks WHERE quest_id = ?")
         .bind(quest_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
-    // Insert new tasks
-    for (task_type, target, applications, external_ids) in tasks {
-        sqlx::query(
-            r#"
-            INSERT INTO quest_tasks (quest_id, task_type, target, applications, external_ids)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(quest_id)
-        .bind(task_type)
-        .bind(target)
-        .bind(applications)
-        .bind(external_ids)
-        .execute(pool)
-        .await
-        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    if tasks.is_empty() {
+        return Ok(());
     }
 
+    // Insert new tasks in one statement
+    QueryBuilder::<MySql>::new(
+        r#"
+        INSERT INTO quest_tasks (
+            quest_id, task_type, target, applications, external_ids, assets, messages
+        )
+        "#,
+    )
+    .push_values(tasks, |mut row, task| {
+        row.push_bind(quest_id)
+            .push_bind(&task.task_type)
+            .push_bind(task.target)
+            .push_bind(&task.applications)
+            .push_bind(&task.external_ids)
+            .push_bind(&task.assets)
+            .push_bind(&task.messages);
+    })
+    .build()
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
     Ok(())
 }
 
 /// Delete and re-insert quest rewards
 pub async fn replace_quest_rewards(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     quest_id: &str,
-    rewards: &[(
-        i32,
-        Option<String>,
-        String,
-        String,
-        Option<i32>,
-        Option<JsonValue>,
-        i32,
-    )],
+    rewards: &[QuestReward],
 ) -> Result<(), ApiError> {
     // Delete existing rewards
     sqlx::query("DELETE FROM quest_rewards WHERE quest_id = ?")
         .bind(quest_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
-    // Insert new rewards
-    for (
-        reward_type,
-        sku_id,
-        reward_name,
-        reward_name_with_article,
-        orb_quantity,
-        redemption_instructions,
-        platform,
-    ) in rewards
-    {
-        sqlx::query(
-            r#"
-            INSERT INTO quest_rewards (
-                quest_id, reward_type, sku_id, reward_name, reward_name_with_article,
-                orb_quantity, redemption_instructions, platform
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(quest_id)
-        .bind(reward_type)
-        .bind(sku_id)
-        .bind(reward_name)
-        .bind(reward_name_with_article)
-        .bind(orb_quantity)
-        .bind(redemption_instructions)
-        .bind(platform)
-        .execute(pool)
-        .await
-        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    if rewards.is_empty() {
+        return Ok(());
     }
 
+    // Insert new rewards in one statement
+    QueryBuilder::<MySql>::new(
+        r#"
+        INSERT INTO quest_rewards (
+            quest_id, reward_type, sku_id, reward_name, reward_name_with_article,
+            orb_quantity, redemption_instructions
+        )
+        "#,
+    )
+    .push_values(rewards, |mut row, reward| {
+        row.push_bind(quest_id)
+            .push_bind(reward.reward_type)
+            .push_bind(&reward.sku_id)
+            .push_bind(&reward.reward_name)
+            .push_bind(&reward.reward_name_with_article)
+            .push_bind(reward.orb_quantity)
+            .push_bind(&reward.redemption_instructions);
+    })
+    .build()
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
     Ok(())
 }
 
 /// Delete and re-insert quest features
 pub async fn replace_quest_features(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     quest_id: &str,
     features: &[i32],
 ) -> Result<(), ApiError> {
     // Delete existing features
     sqlx::query("DELETE FROM quest_features WHERE quest_id = ?")
         .bind(quest_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
-    // Insert new features
-    for feature_id in features {
-        sqlx::query("INSERT INTO quest_features (quest_id, feature_id) VALUES (?, ?)")
-            .bind(quest_id)
-            .bind(feature_id)
-            .execute(pool)
-            .await
-            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    if features.is_empty() {
+        return Ok(());
+    }
+
+    // Insert new features in one statement
+    QueryBuilder::<MySql>::new("INSERT INTO quest_features (quest_id, feature_id) ")
+        .push_values(features, |mut row, feature_id| {
+            row.push_bind(quest_id).push_bind(feature_id);
+        })
+        .build()
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Delete and re-insert quest platforms, keeping their upstream order
+pub async fn replace_quest_platforms(
+    conn: &mut MySqlConnection,
+    quest_id: &str,
+    platforms: &[i32],
+) -> Result<(), ApiError> {
+    // Delete existing platforms
+    sqlx::query("DELETE FROM quest_platforms WHERE quest_id = ?")
+        .bind(quest_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    if platforms.is_empty() {
+        return Ok(());
     }
 
+    // Insert new platforms in one statement
+    QueryBuilder::<MySql>::new("INSERT INTO quest_platforms (quest_id, platform) ")
+        .push_values(platforms, |mut row, platform| {
+            row.push_bind(quest_id).push_bind(platform);
+        })
+        .build()
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
     Ok(())
 }
 
-/// Get all quests with their related data
+// Get all quests with their related data
 // pub async fn get_all_complete_quests(pool: &MySqlPool) -> Result<Vec<CompleteQuest>, ApiError> {
 //     // Get all quests
 //     let quests = sqlx::query_as::<_, Quest>("SELECT * FROM quests ORDER BY starts_at DESC")
@@ -258,73 +290,217 @@ pub async fn get_recent_complete_quests(
     pool: &MySqlPool,
     age_days: i64,
 ) -> Result<Vec<CompleteQuest>, ApiError> {
+    get_filtered_complete_quests(pool, &QuestFilter::default(), age_days).await
+}
+
+/// Get quests matching `filter` with their related data, newest first
+///
+/// `default_age_days` applies unless the filter sets its own `age_days`.
+pub async fn get_filtered_complete_quests(
+    pool: &MySqlPool,
+    filter: &QuestFilter,
+    default_age_days: i64,
+) -> Result<Vec<CompleteQuest>, ApiError> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let mut tx = begin_snapshot(&mut conn).await?;
+
     // Get quests from the last N days
-    let quests = sqlx::query_as::<_, Quest>(
-        "SELECT * FROM quests 
-         WHERE expires_at >= DATE_SUB(NOW(), INTERVAL ? DAY)
-         ORDER BY starts_at DESC",
-    )
-    .bind(age_days)
-    .fetch_all(pool)
-    .await
-    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let mut query = QueryBuilder::<MySql>::new(
+        "SELECT * FROM quests WHERE expires_at >= DATE_SUB(UTC_TIMESTAMP(), INTERVAL ",
+    );
+    query
+        .push_bind(filter.age_days.unwrap_or(default_age_days))
+        .push(" DAY)");
+
+    match filter.status {
+        Some(QuestStatus::Active) => {
+            query.push(" AND starts_at <= UTC_TIMESTAMP() AND expires_at > UTC_TIMESTAMP()")
+        }
+        Some(QuestStatus::Upcoming) => query.push(" AND starts_at > UTC_TIMESTAMP()"),
+        Some(QuestStatus::Expired) => query.push(" AND expires_at <= UTC_TIMESTAMP()"),
+        None => &mut query,
+    };
+
+    if let Some(application_id) = &filter.application_id {
+        query
+            .push(" AND application_id = ")
+            .push_bind(application_id);
+    }
+
+    if let Some(publisher) = &filter.publisher {
+        query.push(" AND game_publisher = ").push_bind(publisher);
+    }
+
+    if let Some(task_type) = &filter.task_type {
+        query
+            .push(" AND EXISTS (SELECT 1 FROM quest_tasks t WHERE t.quest_id = quests.id AND t.task_type = ")
+            .push_bind(task_type)
+            .push(")");
+    }
+
+    if let Some(category) = filter.reward_type {
+        query.push(
+            " AND EXISTS (SELECT 1 FROM quest_rewards r WHERE r.quest_id = quests.id AND r.reward_type ",
+        );
+        match category.reward_type() {
+            Some(reward_type) => {
+                query.push("= ").push_bind(reward_type);
+            }
+            None => {
+                let mut known = query.push("NOT IN (").separated(", ");
+                for reward_type in KNOWN_REWARD_TYPES {
+                    known.push_bind(reward_type);
+                }
+                known.push_unseparated(")");
+            }
+        }
+        query.push(")");
+    }
+
+    // Cross-platform rewards are redeemable everywhere
+    if let Some(platform) = filter.platform {
+        query
+            .push(" AND EXISTS (SELECT 1 FROM quest_platforms p WHERE p.quest_id = quests.id AND p.platform IN (")
+            .push_bind(Platform::CrossPlatform.id())
+            .push(", ")
+            .push_bind(platform.id())
+            .push("))");
+    }
+
+    if let Some(min_orbs) = filter.min_orbs {
+        query
+            .push(" AND EXISTS (SELECT 1 FROM quest_rewards r WHERE r.quest_id = quests.id AND r.orb_quantity >= ")
+            .push_bind(min_orbs)
+            .push(")");
+    }
+
+    if let Some(preview) = filter.preview {
+        query.push(" AND preview = ").push_bind(preview);
+    }
+
+    query.push(" ORDER BY starts_at DESC");
+
+    let quests = query
+        .build_query_as::<Quest>()
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     let mut complete_quests = Vec::new();
 
     for quest in quests {
-        let complete_quest = get_complete_quest_by_id(pool, &quest.id).await?;
-        complete_quests.push(complete_quest);
+        complete_quests.push(load_quest_children(&mut tx, quest).await?);
     }
 
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
     Ok(complete_quests)
 }
 
 /// Get a complete quest by ID with all related data
-async fn get_complete_quest_by_id(
+pub async fn get_complete_quest_by_id(
     pool: &MySqlPool,
     quest_id: &str,
-) -> Result<CompleteQuest, ApiError> {
-    let quest = sqlx::query_as::<_, Quest>("SELECT * FROM quests WHERE id = ?")
-        .bind(quest_id)
-        .fetch_one(pool)
+) -> Result<Option<CompleteQuest>, ApiError> {
+    let mut conn = pool
+        .acquire()
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let mut tx = begin_snapshot(&mut conn).await?;
 
-    // Get assets
-    let assets = sqlx::query_as::<_, QuestAssets>("SELECT * FROM quest_assets WHERE quest_id = ?")
+    let Some(quest) = sqlx::query_as::<_, Quest>("SELECT * FROM quests WHERE id = ?")
         .bind(quest_id)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?
+    else {
+        return Ok(None);
+    };
+
+    let complete_quest = load_quest_children(&mut tx, quest).await?;
+
+    tx.commit()
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
-    // Get tasks
-    let tasks = sqlx::query_as::<_, QuestTask>("SELECT * FROM quest_tasks WHERE quest_id = ?")
+    Ok(Some(complete_quest))
+}
+
+/// Start a transaction whose reads all see the same snapshot, so a quest and
+/// its child rows are never read from either side of a concurrent save
+async fn begin_snapshot(
+    conn: &mut PoolConnection<MySql>,
+) -> Result<Transaction<'_, MySql>, ApiError> {
+    // Applies to the next transaction on this connection only
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+        .execute(&mut **conn)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    conn.begin()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))
+}
+
+/// Load the assets, tasks, rewards, features, platforms and user statuses of a quest
+async fn load_quest_children(
+    conn: &mut MySqlConnection,
+    quest: Quest,
+) -> Result<CompleteQuest, ApiError> {
+    let quest_id = quest.id.as_str();
+
+    // Get assets
+    let assets = sqlx::query_as::<_, QuestAssets>("SELECT * FROM quest_assets WHERE quest_id = ?")
         .bind(quest_id)
-        .fetch_all(pool)
+        .fetch_optional(&mut *conn)
         .await
         .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
-    // Get rewards
-    let rewards =
-        sqlx::query_as::<_, QuestReward>("SELECT * FROM quest_rewards WHERE quest_id = ?")
+    // Get tasks
+    let tasks =
+        sqlx::query_as::<_, QuestTask>("SELECT * FROM quest_tasks WHERE quest_id = ? ORDER BY id")
             .bind(quest_id)
-            .fetch_all(pool)
+            .fetch_all(&mut *conn)
             .await
             .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
+    // Get rewards
+    let rewards = sqlx::query_as::<_, QuestReward>(
+        "SELECT * FROM quest_rewards WHERE quest_id = ? ORDER BY id",
+    )
+    .bind(quest_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
     // Get features
-    let features =
-        sqlx::query_as::<_, QuestFeature>("SELECT * FROM quest_features WHERE quest_id = ?")
-            .bind(quest_id)
-            .fetch_all(pool)
-            .await
-            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let features = sqlx::query_as::<_, QuestFeature>(
+        "SELECT * FROM quest_features WHERE quest_id = ? ORDER BY id",
+    )
+    .bind(quest_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    // Get platforms
+    let platforms = sqlx::query_as::<_, QuestPlatform>(
+        "SELECT * FROM quest_platforms WHERE quest_id = ? ORDER BY id",
+    )
+    .bind(quest_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
     // Get user statuses
     let user_statuses =
         sqlx::query_as::<_, QuestUserStatus>("SELECT * FROM quest_user_status WHERE quest_id = ?")
             .bind(quest_id)
-            .fetch_all(pool)
+            .fetch_all(&mut *conn)
             .await
             .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
 
@@ -334,6 +510,7 @@ async fn get_complete_quest_by_id(
         tasks,
         rewards,
         features,
+        platforms,
         user_statuses,
     })
 }
@@ -347,3 +524,214 @@ pub async fn get_existing_quest_ids(pool: &MySqlPool) -> Result<Vec<String>, Api
 
     Ok(ids.into_iter().map(|(id,)| id).collect())
 }
+
+/// Whether a quest with this ID is stored
+pub async fn quest_exists(pool: &MySqlPool, quest_id: &str) -> Result<bool, ApiError> {
+    let row: Option<(String,)> = sqlx::query_as("SELECT id FROM quests WHERE id = ?")
+        .bind(quest_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(row.is_some())
+}
+
+/// Append a revision for a quest, numbering it after the latest stored one
+///
+/// Meant to run in the transaction that saved the quest, which holds its row
+/// lock; `(quest_id, revision)` is unique, so a racing writer fails instead of
+/// reusing a number.
+pub async fn insert_quest_revision(
+    conn: &mut MySqlConnection,
+    quest_id: &str,
+    config_version: i32,
+    snapshot: &JsonValue,
+    changes: &JsonValue,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO quest_revisions (quest_id, revision, config_version, snapshot, changes, observed_at)
+        SELECT ?, COALESCE(MAX(revision), 0) + 1, ?, ?, ?, UTC_TIMESTAMP()
+        FROM quest_revisions
+        WHERE quest_id = ?
+        "#,
+    )
+    .bind(quest_id)
+    .bind(config_version)
+    .bind(snapshot)
+    .bind(changes)
+    .bind(quest_id)
+    .execute(conn)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Whether any revision has been recorded for a quest
+pub async fn has_quest_revisions(
+    conn: &mut MySqlConnection,
+    quest_id: &str,
+) -> Result<bool, ApiError> {
+    let row: Option<(i64,)> =
+        sqlx::query_as("SELECT id FROM quest_revisions WHERE quest_id = ? LIMIT 1")
+            .bind(quest_id)
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(row.is_some())
+}
+
+/// Get all revisions of a quest, oldest first
+pub async fn get_quest_revisions(
+    pool: &MySqlPool,
+    quest_id: &str,
+) -> Result<Vec<QuestRevision>, ApiError> {
+    let revisions = sqlx::query_as::<_, QuestRevision>(
+        "SELECT * FROM quest_revisions WHERE quest_id = ? ORDER BY revision",
+    )
+    .bind(quest_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(revisions)
+}
+
+/// Insert or refresh an excluded quest, keeping a known replacement link
+pub async fn upsert_excluded_quest(
+    pool: &MySqlPool,
+    quest_id: &str,
+    replacement_id: Option<&str>,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO excluded_quests (id, replacement_id, excluded_at, first_seen_at, last_seen_at)
+        VALUES (?, ?, UTC_TIMESTAMP(), UTC_TIMESTAMP(), UTC_TIMESTAMP())
+        ON DUPLICATE KEY UPDATE
+            replacement_id = COALESCE(VALUES(replacement_id), replacement_id),
+            last_seen_at = UTC_TIMESTAMP()
+        "#,
+    )
+    .bind(quest_id)
+    .bind(replacement_id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Mark excluded quests as seen again without changing anything else
+///
+/// Used when the payload is identical to the last ingested one, so
+/// `last_seen_at` keeps advancing while Discord still reports the quests.
+pub async fn touch_excluded_quests(pool: &MySqlPool, quest_ids: &[&str]) -> Result<u64, ApiError> {
+    if quest_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut query = QueryBuilder::<MySql>::new(
+        "UPDATE excluded_quests SET last_seen_at = UTC_TIMESTAMP() WHERE id IN (",
+    );
+    let mut ids = query.separated(", ");
+    for quest_id in quest_ids {
+        ids.push_bind(*quest_id);
+    }
+    ids.push_unseparated(")");
+
+    let result = query
+        .build()
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(result.rows_affected())
+}
+
+/// Get excluded quests seen within the last N days, linked to their successors
+pub async fn get_excluded_quests(
+    pool: &MySqlPool,
+    age_days: i64,
+) -> Result<Vec<ExcludedQuest>, ApiError> {
+    let excluded = sqlx::query_as::<_, ExcludedQuest>(
+        r#"
+        SELECT e.id, e.replacement_id, e.excluded_at, e.first_seen_at, e.last_seen_at,
+               q.quest_name AS quest_name, r.quest_name AS replacement_quest_name
+        FROM excluded_quests e
+        LEFT JOIN quests q ON q.id = e.id
+        LEFT JOIN quests r ON r.id = e.replacement_id
+        WHERE e.last_seen_at >= DATE_SUB(UTC_TIMESTAMP(), INTERVAL ? DAY)
+        ORDER BY e.last_seen_at DESC, e.id
+        "#,
+    )
+    .bind(age_days)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(excluded)
+}
+
+/// Exclusion record of one quest, however long ago it was last reported
+pub async fn get_excluded_quest(
+    pool: &MySqlPool,
+    quest_id: &str,
+) -> Result<Option<ExcludedQuest>, ApiError> {
+    sqlx::query_as::<_, ExcludedQuest>(
+        r#"
+        SELECT e.id, e.replacement_id, e.excluded_at, e.first_seen_at, e.last_seen_at,
+               q.quest_name AS quest_name, r.quest_name AS replacement_quest_name
+        FROM excluded_quests e
+        LEFT JOIN quests q ON q.id = e.id
+        LEFT JOIN quests r ON r.id = e.replacement_id
+        WHERE e.id = ?
+        "#,
+    )
+    .bind(quest_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))
+}
+
+/// Quarantine a quest that failed to ingest
+pub async fn insert_ingest_failure(
+    pool: &MySqlPool,
+    quest_id: Option<&str>,
+    stage: &str,
+    error: &str,
+    raw: &JsonValue,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"
+        INSERT INTO quest_ingest_failures (quest_id, stage, error, raw, failed_at)
+        VALUES (?, ?, ?, ?, UTC_TIMESTAMP())
+        "#,
+    )
+    .bind(quest_id)
+    .bind(stage)
+    .bind(error)
+    .bind(raw)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Get the most recent ingest failures
+pub async fn get_ingest_failures(
+    pool: &MySqlPool,
+    limit: u64,
+) -> Result<Vec<QuestIngestFailure>, ApiError> {
+    let failures = sqlx::query_as::<_, QuestIngestFailure>(
+        "SELECT * FROM quest_ingest_failures ORDER BY failed_at DESC, id DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(failures)
+}
@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use sqlx::mysql::MySqlPool;
+
+use crate::{
+    config::Config,
+    db::operations::{delete_cache_variants, upsert_cache},
+    sources::directory::{collect_files, read_quest_file},
+    utils::{
+        quest_filter::QuestFilter,
+        quest_parser::{apply_ingest_plan, plan_discord_quests, IngestPlan, QuestAction},
+        refresh::{build_quest_list, QUEST_CACHE_KEY},
+    },
+};
+
+const USAGE: &str = "Usage: kythia-quest-api import [--dry-run] <file|dir>...
+
+Ingests files containing the Discord /quests/@me response shape and rebuilds
+the quest cache. Directories are scanned (non-recursively) for *.json files,
+which are imported in file name order.
+
+Options:
+  --dry-run   Print what would be inserted/updated without writing anything
+  -h, --help  Show this help";
+
+/// Run the `import` subcommand with the arguments that follow it
+pub async fn run(args: &[String]) -> anyhow::Result<()> {
+    let mut dry_run = false;
+    let mut inputs = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--dry-run" => dry_run = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') => {
+                anyhow::bail!("Unknown option: {}\n\n{}", flag, USAGE);
+            }
+            path => inputs.push(PathBuf::from(path)),
+        }
+    }
+
+    if inputs.is_empty() {
+        anyhow::bail!("No input files given\n\n{}", USAGE);
+    }
+
+    let files = collect_files(&inputs)?;
+    if files.is_empty() {
+        anyhow::bail!("No .json files found in the given paths");
+    }
+
+    let config = Config::from_env()?;
+    let db = MySqlPool::connect(config.database_url.expose()).await?;
+
+    if dry_run {
+        println!("🔍 Dry run - the database will not be modified");
+    } else {
+        sqlx::migrate!("./migrations").run(&db).await?;
+    }
+
+    let mut total_inserted = 0;
+    let mut total_updated = 0;
+    let mut total_unchanged = 0;
+    let mut total_failed = 0;
+
+    for file in &files {
+        let response = read_quest_file(file)?;
+        let plan = plan_discord_quests(&db, &response).await?;
+
+        println!("📄 {}", file.display());
+        print_plan(&plan);
+
+        let summary = if dry_run {
+            plan.summary()
+        } else {
+            apply_ingest_plan(&db, &plan).await?
+        };
+
+        total_inserted += summary.inserted;
+        total_updated += summary.updated;
+        total_unchanged += summary.unchanged;
+        total_failed += summary.failed;
+    }
+
+    let verb = if dry_run { "Would insert" } else { "Inserted" };
+    println!(
+        "✅ {} {} new, {} {} changed, {} unchanged quest(s) from {} file(s)",
+        verb,
+        total_inserted,
+        if dry_run { "update" } else { "updated" },
+        total_updated,
+        total_unchanged,
+        files.len()
+    );
+
+    if total_failed > 0 {
+        println!(
+            "⚠️  {} quest(s) could not be {}",
+            total_failed,
+            if dry_run {
+                "parsed"
+            } else {
+                "ingested and were quarantined"
+            }
+        );
+    }
+
+    if dry_run {
+        if files.len() > 1 {
+            println!(
+                "ℹ️  Each file was compared with the current database, not with earlier files"
+            );
+        }
+        return Ok(());
+    }
+
+    // Rebuild the cached response from the imported data
+    let reconstructed = build_quest_list(&db, &config, &QuestFilter::default()).await?;
+    upsert_cache(&db, QUEST_CACHE_KEY, &reconstructed).await?;
+    delete_cache_variants(&db, QUEST_CACHE_KEY).await?;
+    println!("✅ Cache rebuilt for {}", QUEST_CACHE_KEY);
+
+    Ok(())
+}
+
+fn print_plan(plan: &IngestPlan) {
+    for planned in &plan.quests {
+        let quest = &planned.quest.quest;
+        match &planned.action {
+            QuestAction::Insert => println!("   + insert {} ({})", quest.id, quest.quest_name),
+            QuestAction::Update { changes, .. } => {
+                println!("   ~ update {} ({})", quest.id, quest.quest_name);
+                for change in changes {
+                    println!("       {}", change.path);
+                }
+            }
+            QuestAction::Unchanged => {
+                tracing::debug!("   = unchanged {} ({})", quest.id, quest.quest_name)
+            }
+        }
+    }
+
+    for failure in &plan.failures {
+        println!(
+            "   ! failed {} ({}): {}",
+            failure.quest_id.as_deref().unwrap_or("<unknown id>"),
+            failure.stage,
+            failure.error
+        );
+    }
+
+    for excluded in &plan.excluded_quests {
+        match &excluded.replacement_id {
+            Some(replacement) => println!(
+                "   - excluded {} (replaced by {})",
+                excluded.id, replacement
+            ),
+            None => println!("   - excluded {}", excluded.id),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+pub mod config;
+pub mod db;
+pub mod import;
+pub mod mock_discord;
+pub mod routes;
+pub mod sources;
+pub mod utils;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::get,
+    Router,
+};
+use serde_json::Value as JsonValue;
+use sqlx::mysql::MySqlPool;
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::Instant,
+};
+use tower_http::{
+    cors::{Any, CorsLayer},
+    trace::{DefaultMakeSpan, DefaultOnFailure, DefaultOnResponse, TraceLayer},
+};
+
+use crate::config::Config;
+use crate::sources::QuestSource;
+use crate::utils::{
+    asset_mirror::AssetMirror,
+    error::ApiError,
+    refresh::LastIngest,
+    single_flight::SingleFlight,
+    upstream_guard::{UpstreamGuard, UpstreamPolicy},
+};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub db: MySqlPool,
+    pub config: Arc<Config>,
+    pub source: Arc<dyn QuestSource>,
+    pub upstream: Arc<UpstreamGuard>,
+    pub last_ingest: Arc<RwLock<Option<LastIngest>>>,
+    pub refresh_flight: Arc<SingleFlight<Result<JsonValue, ApiError>>>,
+    /// When a stale response last triggered a background refresh
+    pub last_revalidation: Arc<Mutex<Option<Instant>>>,
+    /// Local asset store, when `ASSET_MIRROR_DIR` is set
+    pub asset_mirror: Option<Arc<AssetMirror>>,
+}
+
+impl AppState {
+    pub fn new(db: MySqlPool, config: Config, source: Arc<dyn QuestSource>) -> Self {
+        let asset_mirror = match AssetMirror::from_config(&config) {
+            Ok(mirror) => mirror.map(Arc::new),
+            Err(e) => {
+                tracing::error!("❌ Asset mirroring disabled: {}", e);
+                None
+            }
+        };
+
+        Self {
+            db,
+            upstream: Arc::new(UpstreamGuard::new(UpstreamPolicy::from_config(&config))),
+            config: Arc::new(config),
+            source,
+            last_ingest: Arc::new(RwLock::new(None)),
+            refresh_flight: Arc::new(SingleFlight::new()),
+            last_revalidation: Arc::new(Mutex::new(None)),
+            asset_mirror,
+        }
+    }
+}
+
+/// Build the full HTTP application: health check, `/v1` API and 404 fallback
+pub fn build_router(app_state: AppState) -> Router {
+    // Build router with API routes
+    let mut api_router = Router::new()
+        .nest("/quests", routes::quests::router())
+        .nest("/assets", routes::assets::router());
+
+    // Admin endpoints only exist when they can be authenticated
+    if app_state.config.admin_token.is_some() {
+        api_router = api_router.nest("/admin", routes::admin::router(app_state.clone()));
+    }
+
+    Router::new()
+        .route("/health", get(routes::health::health_check))
+        .nest("/v1", api_router)
+        .fallback(not_found_handler)
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(DefaultMakeSpan::default().include_headers(true))
+                .on_response(DefaultOnResponse::default())
+                .on_failure(DefaultOnFailure::default()),
+        )
+        .layer(
+            CorsLayer::new()
+                .allow_origin(Any)
+                .allow_methods([
+                    axum::http::Method::GET,
+                    axum::http::Method::POST,
+                    axum::http::Method::PUT,
+                    axum::http::Method::DELETE,
+                ])
+                .allow_headers(Any),
+        )
+        .with_state(app_state)
+}
+
+// 404 handler
+async fn not_found_handler() -> impl IntoResponse {
+    let body = serde_json::json!({
+        "error": "Not Found",
+        "message": "The requested endpoint does not exist",
+        "status": 404
+    });
+
+    (StatusCode::NOT_FOUND, Json(body))
+}
@@ -1,29 +1,14 @@
-mod config;
-mod db;
-mod routes;
-mod utils;
-
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Json},
-    routing::get,
-    Router,
-};
 use sqlx::mysql::MySqlPool;
-use std::{net::SocketAddr, sync::Arc};
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::{DefaultMakeSpan, DefaultOnFailure, DefaultOnResponse, TraceLayer},
-};
+use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::config::Config;
-
-#[derive(Clone)]
-pub struct AppState {
-    pub db: MySqlPool,
-    pub config: Arc<Config>,
-}
+use kythia_quest_api::{
+    build_router,
+    config::{Config, RefreshMode},
+    import, sources,
+    utils::{refresh::refresh_quest_cache, scheduler::RefreshScheduler},
+    AppState,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -36,15 +21,23 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Dispatch CLI subcommands before starting the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("import") {
+        return import::run(&args[1..]).await;
+    }
+
     tracing::info!("🚀 Starting Kythia Quest API");
 
     // Load configuration
     let config = Config::from_env()?;
+    let source = sources::from_config(&config)?;
     tracing::info!("✅ Configuration loaded");
+    tracing::info!("🔌 Quest source: {}", source.describe());
 
     // Connect to database
     tracing::info!("🔗 Connecting to database...");
-    let db = MySqlPool::connect(&config.database_url).await?;
+    let db = MySqlPool::connect(config.database_url.expose()).await?;
     tracing::info!("✅ Database connected");
 
     // Run migrations
@@ -53,43 +46,38 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("✅ Migrations completed");
 
     // Create application state
-    let app_state = AppState {
-        db: db.clone(),
-        config: Arc::new(config.clone()),
-    };
+    let app_state = AppState::new(db, config.clone(), source);
 
     // Fetch quests on startup to pre-populate database and cache
     tracing::info!("🚀 Fetching initial quest data...");
-    match fetch_and_cache_quests(&app_state).await {
-        Ok(_) => tracing::info!("✅ Initial quest data loaded"),
-        Err(e) => tracing::warn!("⚠️  Failed to fetch initial quests: {}", e),
-    }
+    let initial_refresh_ok = match refresh_quest_cache(&app_state).await {
+        Ok(_) => {
+            tracing::info!("✅ Initial quest data loaded");
+            true
+        }
+        Err(e) => {
+            tracing::warn!("⚠️  Failed to fetch initial quests: {}", e);
+            false
+        }
+    };
 
-    // Build router with API routes
-    let api_router = Router::new().nest("/quests", routes::quests::router());
-
-    let app = Router::new()
-        .route("/health", get(routes::health::health_check))
-        .nest("/v1", api_router)
-        .fallback(not_found_handler)
-        .layer(
-            TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::default().include_headers(true))
-                .on_response(DefaultOnResponse::default())
-                .on_failure(DefaultOnFailure::default()),
-        )
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods([
-                    axum::http::Method::GET,
-                    axum::http::Method::POST,
-                    axum::http::Method::PUT,
-                    axum::http::Method::DELETE,
-                ])
-                .allow_headers(Any),
-        )
-        .with_state(app_state);
+    // Keep the cache fresh in the background so requests never wait on upstream
+    let scheduler = match config.refresh_mode {
+        RefreshMode::Background => {
+            tracing::info!(
+                "⏲️  Background refresh every {:?} (±{}%)",
+                config.refresh_interval(),
+                config.refresh_jitter_percent
+            );
+            Some(RefreshScheduler::spawn(
+                app_state.clone(),
+                initial_refresh_ok,
+            ))
+        }
+        RefreshMode::OnDemand => None,
+    };
+
+    let app = build_router(app_state);
 
     // Get server address
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
@@ -100,55 +88,21 @@ async fn main() -> anyhow::Result<()> {
         config.port
     );
     tracing::info!("💚 Health check at http://0.0.0.0:{}/health", config.port);
+    if config.admin_token.is_none() {
+        tracing::warn!("⚠️  ADMIN_TOKEN is not set - admin endpoints are disabled");
+    }
 
     // Start server with graceful shutdown
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(shutdown_signal(scheduler))
         .await
         .unwrap();
 
     Ok(())
 }
 
-// Fetch and cache quests on startup
-async fn fetch_and_cache_quests(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::db::operations::upsert_cache;
-    use crate::utils::discord::fetch_discord_quests;
-    use crate::utils::quest_parser::{reconstruct_discord_response, save_discord_quests_to_db};
-
-    // Fetch from Discord
-    let quests_data = fetch_discord_quests(&state.config.discord_token).await?;
-
-    // Save new quests to database
-    let new_count = save_discord_quests_to_db(&state.db, &quests_data).await?;
-
-    if new_count > 0 {
-        tracing::info!("   → Inserted {} new quest(s)", new_count);
-    } else {
-        tracing::info!("   → Database already up to date");
-    }
-
-    // Reconstruct and cache
-    let reconstructed =
-        reconstruct_discord_response(&state.db, state.config.quest_age_days).await?;
-    upsert_cache(&state.db, "discord_quests", &reconstructed).await?;
-
-    Ok(())
-}
-
-// 404 handler
-async fn not_found_handler() -> impl IntoResponse {
-    let body = serde_json::json!({
-        "error": "Not Found",
-        "message": "The requested endpoint does not exist",
-        "status": 404
-    });
-
-    (StatusCode::NOT_FOUND, Json(body))
-}
-
-async fn shutdown_signal() {
+async fn shutdown_signal(scheduler: Option<RefreshScheduler>) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
             .await
@@ -174,4 +128,8 @@ async fn shutdown_signal() {
             tracing::info!("🛑 Received terminate signal, shutting down gracefully...");
         },
     }
+
+    if let Some(scheduler) = scheduler {
+        scheduler.stop().await;
+    }
 }
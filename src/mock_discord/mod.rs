@@ -0,0 +1,267 @@
+//! A stand-in for Discord's quests endpoint, used by the integration tests and
+//! the `mock_discord` binary. It serves a fixture payload and can be switched
+//! into failure modes to exercise error handling without a real token.
+//!
+//! It also stands in for Discord's CDN: any path under `/quests/` answers with
+//! [`mock_asset_body`], so asset mirroring can be tested offline.
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::{
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+/// Path the mock serves quests on, mirroring Discord's API
+pub const QUESTS_PATH: &str = "/api/v10/quests/@me";
+
+/// Quest payload served when no other fixture is given
+pub const DEFAULT_FIXTURE: &str = include_str!("quests.json");
+
+/// How the mock answers the quests endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockMode {
+    /// Serve the current payload
+    Ok,
+    /// Reject the token like Discord does for a revoked session
+    Unauthorized,
+    /// Rate limit the caller for the given number of seconds
+    RateLimited { retry_after_secs: u64 },
+    /// Fail with the given 5xx status and a non-JSON body
+    ServerError(u16),
+    /// Accept the request but never answer it
+    Timeout,
+    /// Serve the payload after a delay
+    Slow(Duration),
+}
+
+impl FromStr for MockMode {
+    type Err = String;
+
+    /// Parses `ok`, `401`, `429[:secs]`, any `5xx`, `timeout` or `slow:<ms>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, arg) = match s.trim().split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (s.trim(), None),
+        };
+
+        let parse_arg = |default: u64| -> Result<u64, String> {
+            arg.map_or(Ok(default), |arg| {
+                arg.parse()
+                    .map_err(|_| format!("Invalid number '{}' in mock mode '{}'", arg, s))
+            })
+        };
+
+        match name {
+            "ok" => Ok(Self::Ok),
+            "401" | "unauthorized" => Ok(Self::Unauthorized),
+            "429" | "rate_limited" => Ok(Self::RateLimited {
+                retry_after_secs: parse_arg(5)?,
+            }),
+            "timeout" => Ok(Self::Timeout),
+            "slow" => Ok(Self::Slow(Duration::from_millis(parse_arg(2000)?))),
+            code => match code.parse::<u16>() {
+                Ok(status) if (500..600).contains(&status) => Ok(Self::ServerError(status)),
+                _ => Err(format!("Unknown mock mode '{}'", s)),
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+struct MockState {
+    mode: Arc<RwLock<MockMode>>,
+    payload: Arc<RwLock<Value>>,
+    requests: Arc<AtomicUsize>,
+    asset_requests: Arc<AtomicUsize>,
+}
+
+/// Handle to a running mock server
+pub struct MockDiscord {
+    addr: SocketAddr,
+    state: MockState,
+}
+
+impl MockDiscord {
+    /// Start serving `payload` on `addr` (use port 0 for a random free port)
+    pub async fn start(addr: SocketAddr, payload: Value) -> std::io::Result<Self> {
+        let state = MockState {
+            mode: Arc::new(RwLock::new(MockMode::Ok)),
+            payload: Arc::new(RwLock::new(payload)),
+            requests: Arc::new(AtomicUsize::new(0)),
+            asset_requests: Arc::new(AtomicUsize::new(0)),
+        };
+
+        let app = Router::new()
+            .route(QUESTS_PATH, get(serve_quests))
+            .route("/quests/*path", get(serve_asset))
+            .route("/_mock/mode", put(set_mode_handler))
+            .route("/_mock/payload", put(set_payload_handler))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                tracing::error!("❌ Mock Discord server stopped: {}", e);
+            }
+        });
+
+        Ok(Self { addr, state })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Full quests URL, suitable for `DISCORD_API_URL`
+    pub fn quests_url(&self) -> String {
+        format!("http://{}{}", self.addr, QUESTS_PATH)
+    }
+
+    /// Base URL of the stand-in CDN, suitable for `ASSET_CDN_URL`
+    pub fn cdn_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn set_mode(&self, mode: MockMode) {
+        *self.state.mode.write().unwrap() = mode;
+    }
+
+    pub fn set_payload(&self, payload: Value) {
+        *self.state.payload.write().unwrap() = payload;
+    }
+
+    /// Number of quest requests received so far
+    pub fn request_count(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+
+    /// Number of CDN asset requests received so far
+    pub fn asset_request_count(&self) -> usize {
+        self.state.asset_requests.load(Ordering::SeqCst)
+    }
+}
+
+/// The bundled fixture payload
+pub fn default_payload() -> Value {
+    serde_json::from_str(DEFAULT_FIXTURE).expect("bundled fixture is valid JSON")
+}
+
+async fn serve_quests(State(state): State<MockState>, headers: HeaderMap) -> Response {
+    state.requests.fetch_add(1, Ordering::SeqCst);
+
+    let mode = state.mode.read().unwrap().clone();
+
+    if !headers.contains_key(header::AUTHORIZATION) {
+        return unauthorized();
+    }
+
+    match mode {
+        MockMode::Ok => {}
+        MockMode::Unauthorized => return unauthorized(),
+        MockMode::RateLimited { retry_after_secs } => {
+            let retry_after = retry_after_secs.to_string();
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [
+                    ("retry-after", retry_after.clone()),
+                    ("x-ratelimit-reset-after", retry_after),
+                ],
+                Json(json!({
+                    "message": "You are being rate limited.",
+                    "retry_after": retry_after_secs as f64,
+                    "global": false
+                })),
+            )
+                .into_response();
+        }
+        MockMode::ServerError(status) => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::BAD_GATEWAY);
+            return (
+                status,
+                "upstream connect error or disconnect/reset before headers",
+            )
+                .into_response();
+        }
+        MockMode::Timeout => {
+            tokio::time::sleep(Duration::from_secs(24 * 60 * 60)).await;
+        }
+        MockMode::Slow(delay) => {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    let payload = state.payload.read().unwrap().clone();
+    Json(payload).into_response()
+}
+
+/// Content the stand-in CDN serves for an asset path
+///
+/// It depends only on the file name, so the same asset of different quests
+/// has identical content.
+pub fn mock_asset_body(path: &str) -> Vec<u8> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    format!("mock asset {}", file_name).into_bytes()
+}
+
+async fn serve_asset(State(state): State<MockState>, Path(path): Path<String>) -> Response {
+    state.asset_requests.fetch_add(1, Ordering::SeqCst);
+
+    let content_type = match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("html") => "text/html",
+        _ => "application/octet-stream",
+    };
+
+    (
+        [(header::CONTENT_TYPE, content_type)],
+        mock_asset_body(&path),
+    )
+        .into_response()
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(json!({ "message": "401: Unauthorized", "code": 0 })),
+    )
+        .into_response()
+}
+
+async fn set_mode_handler(State(state): State<MockState>, body: String) -> Response {
+    match body.parse::<MockMode>() {
+        Ok(mode) => {
+            tracing::info!("🎭 Mock mode set to {:?}", mode);
+            *state.mode.write().unwrap() = mode;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn set_payload_handler(State(state): State<MockState>, body: Bytes) -> Response {
+    match serde_json::from_slice::<Value>(&body) {
+        Ok(payload) => {
+            tracing::info!("🎭 Mock payload replaced ({} bytes)", body.len());
+            *state.payload.write().unwrap() = payload;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::header::{AUTHORIZATION, CONTENT_DISPOSITION},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    db::{
+        drift_operations::get_schema_drift,
+        payload_operations::{get_payload, list_payloads},
+        quest_operations::get_ingest_failures,
+    },
+    utils::error::ApiError,
+    AppState,
+};
+
+const DEFAULT_LIST_LIMIT: u64 = 50;
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/payloads", get(get_payloads))
+        .route("/payloads/:id", get(download_payload))
+        .route("/schema-drift", get(get_drift))
+        .route("/ingest-failures", get(get_failures))
+        .route_layer(middleware::from_fn_with_state(state, require_admin_token))
+}
+
+/// Reject requests without `Authorization: Bearer <ADMIN_TOKEN>`
+///
+/// The admin routes are only mounted when a token is configured; without one
+/// every request is rejected.
+async fn require_admin_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let authorized = match (&state.config.admin_token, provided) {
+        (Some(expected), Some(provided)) => expected.matches(provided),
+        _ => false,
+    };
+
+    if !authorized {
+        return Err(ApiError::AuthError(
+            "Missing or invalid admin token".to_string(),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    limit: Option<u64>,
+}
+
+async fn get_payloads(
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, 500);
+    let payloads = list_payloads(&state.db, limit).await?;
+
+    Ok(Json(json!({ "payloads": payloads })))
+}
+
+async fn download_payload(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Response, ApiError> {
+    let Some(payload) = get_payload(&state.db, id).await? else {
+        return Err(ApiError::NotFound(format!("Payload {} not found", id)));
+    };
+
+    let disposition = format!(
+        "attachment; filename=\"payload-{}-{}.json\"",
+        payload.id,
+        &payload.sha256[..12]
+    );
+
+    Ok(([(CONTENT_DISPOSITION, disposition)], Json(payload.payload)).into_response())
+}
+
+async fn get_drift(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let fields = get_schema_drift(&state.db).await?;
+
+    Ok(Json(json!({ "fields": fields })))
+}
+
+async fn get_failures(
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> Result<Json<Value>, ApiError> {
+    let limit = params.limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, 500);
+    let failures = get_ingest_failures(&state.db, limit).await?;
+
+    Ok(Json(json!({ "failures": failures })))
+}
@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use crate::{
+    db::asset_operations::get_mirrored_asset,
+    utils::{
+        asset_mirror::{is_content_hash, media_type},
+        error::ApiError,
+    },
+    AppState,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/:hash", get(get_asset))
+}
+
+/// Serve a mirrored asset by the SHA-256 of its content
+///
+/// Content never changes under a hash, so responses are cacheable forever.
+/// Only image and video types are served as such, and never sniffed.
+async fn get_asset(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError::NotFound(format!("No mirrored asset {}", hash));
+
+    let Some(mirror) = &state.asset_mirror else {
+        return Err(not_found());
+    };
+
+    if !is_content_hash(&hash) {
+        return Err(not_found());
+    }
+
+    let Some(asset) = get_mirrored_asset(&state.db, &hash).await? else {
+        return Err(not_found());
+    };
+
+    let etag = format!("\"{}\"", asset.sha256);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (
+            header::CACHE_CONTROL,
+            "public, max-age=31536000, immutable".to_string(),
+        ),
+    ];
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let body = match mirror.read(&asset.sha256).await {
+        Ok(body) => body,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!(
+                "⚠️  Mirrored asset {} is missing from {}",
+                asset.sha256,
+                mirror.dir().display()
+            );
+            return Err(not_found());
+        }
+        Err(e) => {
+            return Err(ApiError::InternalError(format!(
+                "Failed to read asset {}: {}",
+                asset.sha256, e
+            )))
+        }
+    };
+
+    // Rows stored before downloads were restricted may carry any type
+    let content_type = media_type(&asset.content_type).unwrap_or("application/octet-stream");
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        cache_headers,
+        body,
+    )
+        .into_response())
+}
@@ -1,6 +1,33 @@
-use axum::{http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use serde_json::{json, Value};
 
-pub async fn health_check() -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({ "status": "ok" })))
+use crate::{
+    utils::upstream_guard::{AuthState, CircuitState},
+    AppState,
+};
+
+pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let last_ingest = state
+        .last_ingest
+        .read()
+        .ok()
+        .and_then(|last_ingest| last_ingest.clone());
+
+    let upstream = state.upstream.status();
+
+    // Still serving cached data, so degraded rather than unhealthy
+    let status = if upstream.circuit == CircuitState::Open || upstream.auth == AuthState::Invalid {
+        "degraded"
+    } else {
+        "ok"
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": status,
+            "last_ingest": last_ingest,
+            "upstream": upstream,
+        })),
+    )
 }
@@ -1,61 +1,348 @@
-use axum::{extract::State, routing::get, Json, Router};
-use serde_json::Value;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
 
 use crate::{
-    db::operations::{get_cache, is_cache_stale, upsert_cache},
+    config::RefreshMode,
+    db::{
+        asset_operations::get_mirrored_assets,
+        operations::{get_cache, is_cache_stale},
+        quest_operations::{get_excluded_quests, get_quest_revisions, quest_exists},
+    },
     utils::{
-        discord::fetch_discord_quests,
+        asset_mirror::annotate_quest_mirrored_assets,
+        asset_url::{resolve_quest_assets, ImageFormat, ImageHints, ResponseFormat},
         error::ApiError,
-        quest_parser::{reconstruct_discord_response, save_discord_quests_to_db},
+        platform::Platform,
+        quest_filter::{parse_bool, parse_count, QuestFilter, QuestStatus},
+        quest_parser::reconstruct_quest_by_id,
+        refresh::{
+            refresh_filtered_cache, refresh_quest_cache, spawn_revalidation, QUEST_CACHE_KEY,
+        },
+        reward_kind::RewardCategory,
+        upstream_guard::AuthState,
     },
     AppState,
 };
 
-const CACHE_KEY: &str = "discord_quests";
-
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", get(get_quests))
+    Router::new()
+        .route("/", get(get_quests))
+        .route("/excluded", get(get_excluded))
+        .route("/:id", get(get_quest))
+        .route("/:id/revisions", get(get_revisions))
+}
+
+/// Freshness of a served quest list, reported in `X-Cache-Status`
+#[derive(Debug, Clone, Copy)]
+enum CacheStatus {
+    Hit,
+    Stale,
+    Miss,
+}
+
+impl CacheStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Stale => "STALE",
+            CacheStatus::Miss => "MISS",
+        }
+    }
+}
+
+/// Query parameters of `GET /v1/quests`
+#[derive(Debug, Deserialize)]
+struct QuestListParams {
+    /// `active`, `upcoming` or `expired`
+    status: Option<String>,
+    application_id: Option<String>,
+    publisher: Option<String>,
+    task_type: Option<String>,
+    /// Only quests with at least one reward of this category
+    #[serde(alias = "reward")]
+    reward_type: Option<String>,
+    /// Only quests whose rewards can be redeemed on this platform
+    platform: Option<String>,
+    min_orbs: Option<String>,
+    preview: Option<String>,
+    /// Overrides `QUEST_AGE_DAYS`
+    age_days: Option<String>,
+    #[serde(flatten)]
+    assets: AssetParams,
+}
+
+impl QuestListParams {
+    fn filter(&self) -> Result<QuestFilter, ApiError> {
+        // Blank values (`?publisher=`) mean no filter
+        let value = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        Ok(QuestFilter {
+            status: value(&self.status)
+                .map(|status| status.parse::<QuestStatus>())
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            application_id: value(&self.application_id),
+            publisher: value(&self.publisher),
+            task_type: value(&self.task_type).map(|task_type| task_type.to_ascii_uppercase()),
+            reward_type: value(&self.reward_type)
+                .map(|reward| reward.parse::<RewardCategory>())
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            platform: value(&self.platform)
+                .map(|platform| platform.parse::<Platform>())
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            min_orbs: value(&self.min_orbs)
+                .map(|min_orbs| parse_count("min_orbs", &min_orbs))
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            preview: value(&self.preview)
+                .map(|preview| parse_bool("preview", &preview))
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            age_days: value(&self.age_days)
+                .map(|age_days| parse_count("age_days", &age_days))
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+        })
+    }
+}
+
+/// How asset fields are rendered, shared by the quest routes
+#[derive(Debug, Deserialize)]
+struct AssetParams {
+    /// `discord` (default) or `resolved` for absolute asset URLs
+    format: Option<String>,
+    /// Image size hint for resolved asset URLs
+    asset_size: Option<String>,
+    /// Image format hint for resolved asset URLs
+    asset_format: Option<String>,
+}
+
+impl AssetParams {
+    fn parse(&self) -> Result<(ResponseFormat, ImageHints), ApiError> {
+        let format = self
+            .format
+            .as_deref()
+            .map(str::parse::<ResponseFormat>)
+            .transpose()
+            .map_err(ApiError::BadRequest)?
+            .unwrap_or_default();
+        let hints = ImageHints {
+            size: self
+                .asset_size
+                .as_deref()
+                .map(ImageHints::parse_size)
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+            format: self
+                .asset_format
+                .as_deref()
+                .map(str::parse::<ImageFormat>)
+                .transpose()
+                .map_err(ApiError::BadRequest)?,
+        };
+
+        Ok((format, hints))
+    }
+}
+
+async fn get_quests(
+    State(state): State<AppState>,
+    Query(params): Query<QuestListParams>,
+) -> Result<Response, ApiError> {
+    let filter = params.filter()?;
+    let (format, hints) = params.assets.parse()?;
+
+    let (mut data, updated_at, status) = if filter.is_empty() {
+        load_quests(&state).await?
+    } else {
+        load_filtered_quests(&state, &filter).await?
+    };
+
+    if format == ResponseFormat::Resolved {
+        // Mirrored copies win over the CDN; re-read so assets mirrored after
+        // the list was cached are linked too
+        let mirrored = if state.config.rewrites_asset_urls() {
+            Some(get_mirrored_assets(&state.db).await?)
+        } else {
+            None
+        };
+
+        if let Some(quests) = data.get_mut("quests").and_then(Value::as_array_mut) {
+            for quest in quests {
+                if let Some(mirrored) = &mirrored {
+                    annotate_quest_mirrored_assets(quest, mirrored);
+                }
+                resolve_quest_assets(quest, &state.config.asset_base_url, &hints);
+            }
+        }
+    }
+
+    let age = Utc::now()
+        .signed_duration_since(updated_at)
+        .num_seconds()
+        .max(0);
+
+    Ok(quests_response(data, age, status))
+}
+
+/// A filtered quest list, cached under the filter's own key
+///
+/// Freshness follows the full list: a variant built from an older full list is
+/// rebuilt from the database.
+async fn load_filtered_quests(
+    state: &AppState,
+    filter: &QuestFilter,
+) -> Result<(Value, DateTime<Utc>, CacheStatus), ApiError> {
+    let (_, updated_at, status) = load_quests(state).await?;
+    let key = filter.cache_key();
+
+    // Compared by the list it was built from, not its own write time: a variant
+    // built before a refresh may be stored after it
+    if let Some(cache) = get_cache(&state.db, &key).await? {
+        if cache
+            .source_updated_at
+            .is_some_and(|source| source >= updated_at)
+        {
+            tracing::debug!("🎯 Cache hit for {}", key);
+            return Ok((cache.data, updated_at, status));
+        }
+    }
+
+    let data = refresh_filtered_cache(state, filter, updated_at).await?;
+
+    Ok((data, updated_at, status))
 }
 
-async fn get_quests(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+/// The full quest list, from the cache when allowed, with when it was built and its freshness
+async fn load_quests(state: &AppState) -> Result<(Value, DateTime<Utc>, CacheStatus), ApiError> {
     // Check cache for complete response
-    let cached_data = get_cache(&state.db, CACHE_KEY).await?;
+    let cached_data = get_cache(&state.db, QUEST_CACHE_KEY).await?;
+    let background = state.config.refresh_mode == RefreshMode::Background;
 
     if let Some(cache) = cached_data {
-        let is_stale = is_cache_stale(cache.updated_at, state.config.cache_duration_ms());
+        let age = Utc::now()
+            .signed_duration_since(cache.updated_at)
+            .num_seconds()
+            .max(0);
 
-        if !is_stale {
-            tracing::debug!("🎯 Cache hit for {}", CACHE_KEY);
-            return Ok(Json(cache.data));
-        } else {
-            tracing::debug!("⏰ Cache stale for {}", CACHE_KEY);
+        if !is_cache_stale(cache.updated_at, state.config.cache_duration_ms()) {
+            tracing::debug!("🎯 Cache hit for {}", QUEST_CACHE_KEY);
+            return Ok((cache.data, cache.updated_at, CacheStatus::Hit));
+        }
+
+        // Serve stale data while it is within the allowed staleness, or for as
+        // long as upstream rejects our credential; in on-demand mode a refresh
+        // is retried in the background
+        let auth_invalid = state.upstream.status().auth == AuthState::Invalid;
+        if auth_invalid || !is_cache_stale(cache.updated_at, state.config.max_cache_age_ms()) {
+            tracing::debug!("⏰ Serving stale {} ({}s old)", QUEST_CACHE_KEY, age);
+            if !background {
+                spawn_revalidation(state);
+            }
+            return Ok((cache.data, cache.updated_at, CacheStatus::Stale));
+        }
+
+        tracing::debug!(
+            "⌛ Cache for {} is past the maximum staleness",
+            QUEST_CACHE_KEY
+        );
+        if background {
+            return Err(ApiError::Unavailable(
+                "Quest data is older than the maximum staleness".to_string(),
+            ));
         }
     } else {
-        tracing::debug!("❌ Cache miss for {}", CACHE_KEY);
+        tracing::debug!("❌ Cache miss for {}", QUEST_CACHE_KEY);
+
+        // The background scheduler owns refreshing; requests only read the cache
+        if background {
+            return Err(ApiError::Unavailable(
+                "Quest data has not been loaded yet".to_string(),
+            ));
+        }
     }
 
-    // Fetch fresh data from Discord API
-    tracing::info!("📡 Fetching quests from Discord API");
-    let quests_data = fetch_discord_quests(&state.config.discord_token).await?;
+    let reconstructed = refresh_quest_cache(state).await?;
 
-    // Save ONLY NEW quests to database (optimization)
-    tracing::info!("💾 Checking for new quests...");
-    let new_count = save_discord_quests_to_db(&state.db, &quests_data).await?;
+    Ok((reconstructed, Utc::now(), CacheStatus::Miss))
+}
 
-    if new_count > 0 {
-        tracing::info!("✅ Inserted {} new quest(s) to database", new_count);
-    } else {
-        tracing::info!("✅ No new quests - database is up to date");
+/// Quest list with `Age`, `X-Cache-Status` and, when stale, `Warning` headers
+fn quests_response(data: Value, age_seconds: i64, status: CacheStatus) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::AGE, HeaderValue::from(age_seconds));
+    headers.insert(
+        HeaderName::from_static("x-cache-status"),
+        HeaderValue::from_static(status.as_str()),
+    );
+    if let CacheStatus::Stale = status {
+        headers.insert(
+            header::WARNING,
+            HeaderValue::from_static("110 - \"Response is Stale\""),
+        );
+    }
+
+    (headers, Json(data)).into_response()
+}
+
+/// One quest by id, including quests outside the `QUEST_AGE_DAYS` window
+async fn get_quest(
+    State(state): State<AppState>,
+    Path(quest_id): Path<String>,
+    Query(params): Query<AssetParams>,
+) -> Result<Json<Value>, ApiError> {
+    let (format, hints) = params.parse()?;
+
+    let mut quest = reconstruct_quest_by_id(&state.db, &quest_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Quest {} not found", quest_id)))?;
+
+    if state.config.rewrites_asset_urls() {
+        let mirrored = get_mirrored_assets(&state.db).await?;
+        annotate_quest_mirrored_assets(&mut quest, &mirrored);
     }
 
-    // Reconstruct response from database
-    tracing::info!("🔄 Reconstructing response from database");
-    let reconstructed =
-        reconstruct_discord_response(&state.db, state.config.quest_age_days).await?;
+    if format == ResponseFormat::Resolved {
+        resolve_quest_assets(&mut quest, &state.config.asset_base_url, &hints);
+    }
+
+    Ok(Json(quest))
+}
+
+async fn get_excluded(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let excluded = get_excluded_quests(&state.db, state.config.quest_age_days).await?;
+
+    Ok(Json(json!({ "excluded_quests": excluded })))
+}
+
+async fn get_revisions(
+    State(state): State<AppState>,
+    Path(quest_id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    if !quest_exists(&state.db, &quest_id).await? {
+        return Err(ApiError::NotFound(format!("Quest {} not found", quest_id)));
+    }
 
-    // Update cache with reconstructed response
-    upsert_cache(&state.db, CACHE_KEY, &reconstructed).await?;
-    tracing::info!("✅ Cache updated for {}", CACHE_KEY);
+    let revisions = get_quest_revisions(&state.db, &quest_id).await?;
 
-    Ok(Json(reconstructed))
+    Ok(Json(json!({
+        "quest_id": quest_id,
+        "revisions": revisions,
+    })))
 }
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::{json, Value as JsonValue};
+
+use super::QuestSource;
+use crate::utils::{discord::UpstreamResponse, error::ApiError};
+
+/// Every `*.json` file in a directory, read on each fetch
+///
+/// Files are read in name order and combined into one response; when several
+/// files contain the same quest or exclusion, the last file wins. Each file may
+/// hold a full `/quests/@me` response or a bare array of quests.
+pub struct DirectorySource {
+    dir: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl QuestSource for DirectorySource {
+    fn describe(&self) -> String {
+        format!("directory {}", self.dir.display())
+    }
+
+    async fn fetch(&self) -> Result<UpstreamResponse, ApiError> {
+        let dir = self.dir.clone();
+
+        tokio::task::spawn_blocking(move || read_directory(&dir))
+            .await
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .map_err(|e| ApiError::ConfigError(e.to_string()))
+    }
+}
+
+fn read_directory(dir: &Path) -> anyhow::Result<UpstreamResponse> {
+    let files = collect_files(&[dir.to_path_buf()])?;
+    if files.is_empty() {
+        anyhow::bail!("No .json files found in {}", dir.display());
+    }
+
+    let mut quests = Vec::new();
+    let mut excluded_quests = Vec::new();
+    let mut byte_size = 0;
+
+    for file in &files {
+        byte_size += std::fs::metadata(file)?.len() as usize;
+        let response = read_quest_file(file)?;
+
+        merge_by_id(&mut quests, &response["quests"]);
+        merge_by_id(&mut excluded_quests, &response["excluded_quests"]);
+    }
+
+    Ok(UpstreamResponse {
+        status: 200,
+        body: json!({
+            "quests": quests,
+            "excluded_quests": excluded_quests,
+        }),
+        byte_size,
+        retry_after: None,
+    })
+}
+
+/// Append array entries, replacing earlier entries that have the same `id`
+fn merge_by_id(target: &mut Vec<JsonValue>, entries: &JsonValue) {
+    for entry in entries.as_array().into_iter().flatten() {
+        let id = entry.get("id").and_then(|id| id.as_str());
+        let existing = id.and_then(|id| {
+            target
+                .iter()
+                .position(|other| other.get("id").and_then(|other| other.as_str()) == Some(id))
+        });
+
+        match existing {
+            Some(index) => target[index] = entry.clone(),
+            None => target.push(entry.clone()),
+        }
+    }
+}
+
+/// Expand the given paths into a sorted list of JSON files
+pub fn collect_files(inputs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        if input.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(input)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else if input.is_file() {
+            files.push(input.clone());
+        } else {
+            anyhow::bail!("{} does not exist", input.display());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Read a quest file, accepting either the full response or a bare quest array
+pub fn read_quest_file(path: &Path) -> anyhow::Result<JsonValue> {
+    let contents = std::fs::read_to_string(path)?;
+    let value: JsonValue = serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("{} is not valid JSON: {}", path.display(), e))?;
+
+    Ok(match value {
+        JsonValue::Array(quests) => json!({ "quests": quests }),
+        other => other,
+    })
+}
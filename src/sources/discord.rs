@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::QuestSource;
+use crate::utils::{
+    credentials::TokenProvider,
+    discord::{fetch_discord_payload, UpstreamResponse},
+    error::ApiError,
+};
+
+/// Discord's quests endpoint, authenticated with a user token
+pub struct DiscordSource {
+    client: reqwest::Client,
+    api_url: String,
+    token: Arc<TokenProvider>,
+}
+
+impl DiscordSource {
+    pub fn new(client: reqwest::Client, api_url: &str, token: Arc<TokenProvider>) -> Self {
+        Self {
+            client,
+            api_url: api_url.to_string(),
+            token,
+        }
+    }
+}
+
+#[async_trait]
+impl QuestSource for DiscordSource {
+    fn describe(&self) -> String {
+        format!("Discord API ({})", self.api_url)
+    }
+
+    async fn fetch(&self) -> Result<UpstreamResponse, ApiError> {
+        fetch_discord_payload(&self.client, &self.api_url, &self.token.current()).await
+    }
+
+    fn has_credentials(&self) -> bool {
+        true
+    }
+
+    async fn reload_credentials(&self) -> bool {
+        self.token.reload().await
+    }
+}
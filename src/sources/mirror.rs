@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+
+use super::QuestSource;
+use crate::utils::{
+    discord::{read_upstream_response, UpstreamResponse},
+    error::ApiError,
+    quest_parser::ANNOTATIONS_KEY,
+};
+
+/// `/v1/quests` of another instance of this API
+///
+/// Lets a secondary deployment follow a primary without Discord credentials.
+/// The primary's own annotations (exclusion, platform names, task and reward
+/// summaries, mirrored asset URLs, all under `_annotations`) are stripped so
+/// they are not mistaken for new upstream fields.
+pub struct MirrorSource {
+    client: reqwest::Client,
+    quests_url: String,
+}
+
+impl MirrorSource {
+    pub fn new(client: reqwest::Client, base_url: &str) -> Self {
+        Self {
+            client,
+            quests_url: format!("{}/v1/quests", base_url.trim_end_matches('/')),
+        }
+    }
+}
+
+#[async_trait]
+impl QuestSource for MirrorSource {
+    fn describe(&self) -> String {
+        format!("mirror {}", self.quests_url)
+    }
+
+    async fn fetch(&self) -> Result<UpstreamResponse, ApiError> {
+        let response = self
+            .client
+            .get(&self.quests_url)
+            .send()
+            .await
+            .map_err(|e| ApiError::DiscordApiError(format!("Request failed: {}", e)))?;
+
+        let mut response = read_upstream_response(response).await?;
+
+        if let Some(quests) = response
+            .body
+            .get_mut("quests")
+            .and_then(JsonValue::as_array_mut)
+        {
+            for quest in quests.iter_mut().filter_map(JsonValue::as_object_mut) {
+                quest.remove(ANNOTATIONS_KEY);
+            }
+        }
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,67 @@
+//! Where quest payloads come from. Ingest only sees a [`QuestSource`], so a
+//! deployment can follow Discord, a directory of captures or another instance
+//! of this API without any other code knowing the difference.
+
+pub mod directory;
+pub mod discord;
+pub mod mirror;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::{Config, QuestSourceConfig},
+    utils::{
+        credentials::TokenProvider,
+        discord::{build_http_client, UpstreamResponse},
+        error::ApiError,
+    },
+};
+
+pub use directory::DirectorySource;
+pub use discord::DiscordSource;
+pub use mirror::MirrorSource;
+
+/// A provider of payloads in the Discord `/quests/@me` response shape
+#[async_trait]
+pub trait QuestSource: Send + Sync {
+    /// Human-readable description used in logs
+    fn describe(&self) -> String;
+
+    /// Fetch the current payload
+    ///
+    /// Non-2xx answers are returned rather than turned into errors so they can
+    /// be archived; only transport and read failures are errors.
+    async fn fetch(&self) -> Result<UpstreamResponse, ApiError>;
+
+    /// Whether the source authenticates, so a 401 means our credential is bad
+    fn has_credentials(&self) -> bool {
+        false
+    }
+
+    /// Re-read the credential from its origin, returning whether it changed
+    async fn reload_credentials(&self) -> bool {
+        false
+    }
+}
+
+/// Build the source selected by `QUEST_SOURCE`
+pub fn from_config(config: &Config) -> anyhow::Result<Arc<dyn QuestSource>> {
+    let client = || build_http_client(config.upstream_connect_timeout(), config.upstream_timeout());
+
+    let source: Arc<dyn QuestSource> = match &config.quest_source {
+        QuestSourceConfig::Discord => Arc::new(DiscordSource::new(
+            client()?,
+            &config.discord_api_url,
+            Arc::new(TokenProvider::new(
+                config.require_discord_token()?.clone(),
+                config.discord_token_source(),
+            )),
+        )),
+        QuestSourceConfig::Directory(dir) => Arc::new(DirectorySource::new(dir)),
+        QuestSourceConfig::Mirror(base_url) => Arc::new(MirrorSource::new(client()?, base_url)),
+    };
+
+    Ok(source)
+}
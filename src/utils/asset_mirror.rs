@@ -0,0 +1,429 @@
+//! Local copies of quest assets, so embeds keep working when Discord rotates
+//! CDN paths or takes a quest down. Each upstream path is downloaded once and
+//! stored under the SHA-256 of its content, which is also its public address.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::{
+    config::Config,
+    db::{
+        asset_operations::{get_mirrored_assets, get_quest_asset_paths, upsert_mirrored_asset},
+        models::MirroredAsset,
+        quest_models::QuestAssets,
+    },
+    utils::{discord::build_http_client, error::ApiError, quest_parser::quest_annotations},
+};
+
+/// Discord's CDN, used unless `ASSET_CDN_URL` overrides it
+pub const DEFAULT_ASSET_CDN_URL: &str = "https://cdn.discordapp.com";
+
+/// Content types that are mirrored; anything else could be rendered as a page
+/// on our origin, so it is refused at download
+const MEDIA_TYPES: [&str; 7] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+    "video/mp4",
+    "video/webm",
+];
+
+/// Longest wait before a failed asset path is downloaded again
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Outcome of one mirroring pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MirrorSummary {
+    pub downloaded: usize,
+    pub already_mirrored: usize,
+    pub failed: usize,
+    /// Failed earlier and still waiting for their retry
+    pub backed_off: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FailedAsset {
+    failures: u32,
+    retry_at: DateTime<Utc>,
+}
+
+/// Asset paths whose download failed, and when each may be tried again
+///
+/// The first wait is `ASSET_RETRY_BACKOFF_SECONDS`, doubled for each further
+/// failure up to a day. Kept in memory, so a restart retries everything once.
+#[derive(Debug)]
+pub struct RetryBackoff {
+    initial: Duration,
+    failed: Mutex<HashMap<String, FailedAsset>>,
+}
+
+impl RetryBackoff {
+    pub fn new(initial: Duration) -> Self {
+        Self {
+            initial,
+            failed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `path` failed and its retry is still in the future at `now`
+    pub fn is_waiting(&self, path: &str, now: DateTime<Utc>) -> bool {
+        self.failed
+            .lock()
+            .unwrap()
+            .get(path)
+            .is_some_and(|failed| failed.retry_at > now)
+    }
+
+    /// Record a failed download at `now`; returns when `path` may be retried
+    pub fn record_failure(&self, path: &str, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut failed = self.failed.lock().unwrap();
+        let failures = failed.get(path).map_or(1, |f| f.failures.saturating_add(1));
+        let delay = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(failures - 1))
+            .min(MAX_RETRY_BACKOFF);
+        let retry_at = now + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero());
+
+        failed.insert(path.to_string(), FailedAsset { failures, retry_at });
+        retry_at
+    }
+
+    /// Forget earlier failures of a path that downloaded fine
+    pub fn record_success(&self, path: &str) {
+        self.failed.lock().unwrap().remove(path);
+    }
+}
+
+/// Downloads quest assets into `ASSET_MIRROR_DIR`
+pub struct AssetMirror {
+    client: reqwest::Client,
+    cdn_url: String,
+    dir: PathBuf,
+    max_bytes: u64,
+    backoff: RetryBackoff,
+    /// Held for a whole pass so passes never overlap
+    pass: tokio::sync::Mutex<()>,
+}
+
+impl AssetMirror {
+    pub fn new(
+        client: reqwest::Client,
+        cdn_url: &str,
+        dir: impl Into<PathBuf>,
+        max_bytes: u64,
+        retry_backoff: Duration,
+    ) -> Self {
+        Self {
+            client,
+            cdn_url: cdn_url.trim_end_matches('/').to_string(),
+            dir: dir.into(),
+            max_bytes,
+            backoff: RetryBackoff::new(retry_backoff),
+            pass: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// The mirror selected by `ASSET_MIRROR_DIR`, or `None` when mirroring is off
+    pub fn from_config(config: &Config) -> Result<Option<Self>, ApiError> {
+        let Some(dir) = &config.asset_mirror_dir else {
+            return Ok(None);
+        };
+
+        let client =
+            build_http_client(config.upstream_connect_timeout(), config.upstream_timeout())?;
+
+        Ok(Some(Self::new(
+            client,
+            &config.asset_cdn_url,
+            dir,
+            config.asset_max_bytes,
+            Duration::from_secs(config.asset_retry_backoff_seconds),
+        )))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the content with this hash is stored, sharded by its first two characters
+    pub fn file_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(&sha256[..2]).join(sha256)
+    }
+
+    /// Download every asset of the quests that expired within the last
+    /// `age_days` days that is not mirrored yet
+    ///
+    /// Failed paths are skipped until their [`RetryBackoff`] wait has passed.
+    /// A pass that starts while another one runs returns an empty summary.
+    pub async fn mirror_quest_assets(
+        &self,
+        pool: &MySqlPool,
+        age_days: i64,
+    ) -> Result<MirrorSummary, ApiError> {
+        let mut summary = MirrorSummary::default();
+        let Ok(_pass) = self.pass.try_lock() else {
+            tracing::debug!("🖼️  Asset mirroring already running - skipping this pass");
+            return Ok(summary);
+        };
+
+        let paths = get_quest_asset_paths(pool, age_days).await?;
+        let mirrored = get_mirrored_assets(pool).await?;
+
+        for path in paths {
+            if mirrored
+                .get(&path)
+                .is_some_and(|asset| self.file_path(&asset.sha256).exists())
+            {
+                summary.already_mirrored += 1;
+                continue;
+            }
+
+            if self.backoff.is_waiting(&path, Utc::now()) {
+                summary.backed_off += 1;
+                continue;
+            }
+
+            match self.download(&path).await {
+                Ok(asset) => {
+                    upsert_mirrored_asset(pool, &asset).await?;
+                    self.backoff.record_success(&path);
+                    summary.downloaded += 1;
+                }
+                Err(e) => {
+                    let retry_at = self.backoff.record_failure(&path, Utc::now());
+                    tracing::warn!(
+                        "⚠️  Failed to mirror asset {}: {} (next attempt after {})",
+                        path,
+                        e,
+                        retry_at
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Download one upstream asset path into the store
+    pub async fn download(&self, path: &str) -> Result<MirroredAsset, ApiError> {
+        let url = format!("{}/{}", self.cdn_url, path.trim_start_matches('/'));
+
+        let mut response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ApiError::DiscordApiError(format!("Asset request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::DiscordApiError(format!(
+                "CDN answered {} for {}",
+                response.status(),
+                url
+            )));
+        }
+
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_bytes)
+        {
+            return Err(self.too_large(path));
+        }
+
+        let declared = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_else(|| guess_content_type(path));
+        let Some(content_type) = media_type(declared) else {
+            return Err(ApiError::DiscordApiError(format!(
+                "Asset {} is {}, not an image or video",
+                path, declared
+            )));
+        };
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ApiError::DiscordApiError(format!("Failed to read asset: {}", e)))?
+        {
+            body.extend_from_slice(&chunk);
+            if body.len() as u64 > self.max_bytes {
+                return Err(self.too_large(path));
+            }
+        }
+
+        let sha256 = format!("{:x}", Sha256::digest(&body));
+        self.store(&sha256, &body).await?;
+
+        Ok(MirroredAsset {
+            source_path: path.to_string(),
+            sha256,
+            content_type: content_type.to_string(),
+            size_bytes: body.len() as i64,
+        })
+    }
+
+    /// Contents stored under `sha256`
+    pub async fn read(&self, sha256: &str) -> std::io::Result<Vec<u8>> {
+        tokio::fs::read(self.file_path(sha256)).await
+    }
+
+    /// Write content once; identical content from another path reuses the file
+    async fn store(&self, sha256: &str, body: &[u8]) -> Result<(), ApiError> {
+        let path = self.file_path(sha256);
+        if path.exists() {
+            return Ok(());
+        }
+
+        let store_error = |e: std::io::Error| {
+            ApiError::InternalError(format!("Failed to store asset {}: {}", sha256, e))
+        };
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(store_error)?;
+        }
+
+        // Write next to the target and rename, so readers never see a partial file
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, body)
+            .await
+            .map_err(store_error)?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(store_error)?;
+
+        Ok(())
+    }
+
+    fn too_large(&self, path: &str) -> ApiError {
+        ApiError::DiscordApiError(format!(
+            "Asset {} is larger than ASSET_MAX_BYTES ({} bytes)",
+            path, self.max_bytes
+        ))
+    }
+}
+
+/// Asset fields of a quest that are set, with their upstream paths
+pub fn asset_fields(assets: &QuestAssets) -> Vec<(&'static str, &str)> {
+    [
+        ("hero", &assets.hero),
+        ("hero_video", &assets.hero_video),
+        ("quest_bar_hero", &assets.quest_bar_hero),
+        ("quest_bar_hero_video", &assets.quest_bar_hero_video),
+        ("game_tile", &assets.game_tile),
+        ("logotype", &assets.logotype),
+        ("game_tile_light", &assets.game_tile_light),
+        ("game_tile_dark", &assets.game_tile_dark),
+        ("logotype_light", &assets.logotype_light),
+        ("logotype_dark", &assets.logotype_dark),
+    ]
+    .into_iter()
+    .filter_map(|(field, path)| {
+        path.as_deref()
+            .filter(|path| !path.is_empty())
+            .map(|path| (field, path))
+    })
+    .collect()
+}
+
+/// Whether `value` looks like a content address (lowercase hex SHA-256)
+pub fn is_content_hash(value: &str) -> bool {
+    value.len() == 64
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// The allowed media type of a `Content-Type` value, without its parameters
+pub fn media_type(content_type: &str) -> Option<&'static str> {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    MEDIA_TYPES.into_iter().find(|media| *media == essence)
+}
+
+/// Path the mirrored content is served on
+pub fn mirrored_asset_url(sha256: &str) -> String {
+    format!("/v1/assets/{}", sha256)
+}
+
+/// Add `mirrored_assets` to each quest, mapping asset fields to mirrored URLs
+///
+/// Only mirrored fields are listed; the Discord-shaped `config.assets` is left
+/// untouched.
+pub async fn annotate_mirrored_assets(
+    pool: &MySqlPool,
+    response: &mut JsonValue,
+) -> Result<(), ApiError> {
+    let mirrored = get_mirrored_assets(pool).await?;
+
+    if let Some(quests) = response.get_mut("quests").and_then(JsonValue::as_array_mut) {
+        for quest in quests {
+            annotate_quest_mirrored_assets(quest, &mirrored);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add `mirrored_assets` to the annotations of one quest, given every mirrored asset by upstream path
+pub fn annotate_quest_mirrored_assets(
+    quest: &mut JsonValue,
+    mirrored: &HashMap<String, MirroredAsset>,
+) {
+    let urls: Map<String, JsonValue> = quest
+        .pointer("/config/assets")
+        .and_then(JsonValue::as_object)
+        .into_iter()
+        .flatten()
+        .filter_map(|(field, path)| {
+            let asset = mirrored.get(path.as_str()?)?;
+            Some((field.clone(), json!(mirrored_asset_url(&asset.sha256))))
+        })
+        .collect();
+
+    if let Some(annotations) = quest_annotations(quest) {
+        annotations.insert("mirrored_assets".to_string(), JsonValue::Object(urls));
+    }
+}
+
+/// Content type from the file extension, for CDN responses without one
+fn guess_content_type(path: &str) -> &'static str {
+    let extension = path
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
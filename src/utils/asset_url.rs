@@ -0,0 +1,207 @@
+//! Fully qualified asset URLs for the resolved response format. Discord sends
+//! asset keys relative to its CDN (`quests/<id>/<file>.png`); consumers of the
+//! resolved format get URLs they can load directly.
+
+use std::{fmt, str::FromStr};
+
+use serde_json::Value as JsonValue;
+
+/// Shape of the quest list (`?format=`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// Exactly what Discord sent, asset keys included
+    #[default]
+    Discord,
+    /// Asset keys replaced by absolute URLs under `ASSET_BASE_URL`
+    Resolved,
+}
+
+impl FromStr for ResponseFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "discord" => Ok(ResponseFormat::Discord),
+            "resolved" => Ok(ResponseFormat::Resolved),
+            other => Err(format!(
+                "Unknown format '{}' (expected discord or resolved)",
+                other
+            )),
+        }
+    }
+}
+
+/// Image encodings the CDN can convert to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpg,
+    Webp,
+    Gif,
+}
+
+impl ImageFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpg => "jpg",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Gif => "gif",
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "png" => Ok(ImageFormat::Png),
+            "jpg" | "jpeg" => Ok(ImageFormat::Jpg),
+            "webp" => Ok(ImageFormat::Webp),
+            "gif" => Ok(ImageFormat::Gif),
+            other => Err(format!(
+                "Unknown image format '{}' (expected png, jpg, webp or gif)",
+                other
+            )),
+        }
+    }
+}
+
+/// Size and format hints appended to image URLs; videos are left alone
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageHints {
+    pub size: Option<u32>,
+    pub format: Option<ImageFormat>,
+}
+
+impl ImageHints {
+    /// Sizes the CDN accepts: powers of two from 16 to 4096
+    pub fn parse_size(value: &str) -> Result<u32, String> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|size| (16..=4096).contains(size) && size.is_power_of_two())
+            .ok_or_else(|| {
+                format!(
+                    "Invalid image size '{}' (expected a power of two from 16 to 4096)",
+                    value
+                )
+            })
+    }
+
+    fn query(&self) -> Option<String> {
+        let params: Vec<String> = [
+            self.format.map(|format| format!("format={}", format)),
+            self.size.map(|size| format!("size={}", size)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        (!params.is_empty()).then(|| params.join("&"))
+    }
+}
+
+/// Absolute URL of an asset key, with `hints` when it is an image
+///
+/// Values that already are absolute URLs keep their host.
+pub fn resolve_asset_url(base_url: &str, path: &str, hints: &ImageHints) -> String {
+    let mut url = if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    };
+
+    if !is_video(path) {
+        if let Some(query) = hints.query() {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query);
+        }
+    }
+
+    url
+}
+
+/// Replace every asset key of a Discord-shaped quest with its absolute URL
+///
+/// Covers `config.assets` (including light/dark variants and videos), task
+/// videos and their thumbnails, and the video of each `tasks` summary. An
+/// asset listed in the quest's `_annotations.mirrored_assets` (added when
+/// `ASSET_REWRITE_URLS` is on) gets its mirrored URL instead of the CDN's.
+pub fn resolve_quest_assets(quest: &mut JsonValue, base_url: &str, hints: &ImageHints) {
+    let mut resolve = |value: &mut JsonValue| {
+        if let Some(path) = value.as_str().filter(|path| !path.is_empty()) {
+            *value = JsonValue::String(resolve_asset_url(base_url, path, hints));
+        }
+    };
+
+    let mirrored = quest
+        .pointer("/_annotations/mirrored_assets")
+        .and_then(JsonValue::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    // Pointers rather than indexing, which would insert the keys it misses
+    if let Some(assets) = quest
+        .pointer_mut("/config/assets")
+        .and_then(JsonValue::as_object_mut)
+    {
+        for (field, value) in assets.iter_mut() {
+            match mirrored.get(field) {
+                Some(url) if !value.is_null() => *value = url.clone(),
+                _ => resolve(value),
+            }
+        }
+    }
+
+    if let Some(tasks) = quest
+        .pointer_mut("/config/task_config_v2/tasks")
+        .and_then(JsonValue::as_object_mut)
+    {
+        for task in tasks.values_mut() {
+            resolve_video(task.pointer_mut("/assets/video"), &mut resolve);
+        }
+    }
+
+    if let Some(items) = quest
+        .pointer_mut("/_annotations/tasks/items")
+        .and_then(JsonValue::as_array_mut)
+    {
+        for item in items {
+            resolve_video(item.get_mut("video"), &mut resolve);
+        }
+    }
+}
+
+fn resolve_video(video: Option<&mut JsonValue>, resolve: &mut impl FnMut(&mut JsonValue)) {
+    if let Some(video) = video.and_then(JsonValue::as_object_mut) {
+        for field in ["url", "thumbnail"] {
+            if let Some(value) = video.get_mut(field) {
+                resolve(value);
+            }
+        }
+    }
+}
+
+fn is_video(path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    let extension = path
+        .rsplit('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    matches!(extension.as_str(), "mp4" | "webm" | "mov" | "m4v")
+}
@@ -0,0 +1,94 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use super::secret::Secret;
+
+/// Where a replaced Discord token is looked for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenSource {
+    /// `DISCORD_TOKEN_FILE`, re-read on every reload
+    File(PathBuf),
+    /// `DISCORD_TOKEN` in the `.env` file, re-read on every reload
+    DotEnv,
+    /// `DISCORD_TOKEN` set in the process environment. It takes precedence
+    /// over `.env` as it does at startup, and cannot change without a restart.
+    Environment,
+}
+
+/// The Discord token, re-readable without a restart
+///
+/// A reload only sees a changed `DISCORD_TOKEN_FILE`, or a changed `.env`
+/// file when the process environment does not set `DISCORD_TOKEN` itself.
+pub struct TokenProvider {
+    source: TokenSource,
+    current: RwLock<Secret>,
+}
+
+impl TokenProvider {
+    pub fn new(token: impl Into<Secret>, source: TokenSource) -> Self {
+        let token = token.into();
+        Self {
+            source,
+            current: RwLock::new(Secret::new(token.expose().trim())),
+        }
+    }
+
+    pub fn current(&self) -> Secret {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Re-read the token from its source, returning whether it changed
+    ///
+    /// The read never blocks the runtime; the lock is only taken to swap in a
+    /// changed token.
+    pub async fn reload(&self) -> bool {
+        let token = match &self.source {
+            TokenSource::File(path) => match tokio::fs::read_to_string(path).await {
+                Ok(contents) => Some(Secret::new(contents.trim())),
+                Err(e) => {
+                    tracing::warn!(
+                        "⚠️  Could not re-read DISCORD_TOKEN_FILE {}: {}",
+                        path.display(),
+                        e
+                    );
+                    None
+                }
+            },
+            TokenSource::DotEnv => tokio::task::spawn_blocking(read_dotenv_token)
+                .await
+                .ok()
+                .flatten(),
+            TokenSource::Environment => None,
+        };
+
+        let Some(token) = token.filter(|token| !token.is_empty()) else {
+            return false;
+        };
+
+        let mut current = self.current.write().unwrap();
+        if *current == token {
+            return false;
+        }
+
+        *current = token;
+        true
+    }
+}
+
+/// Read a secret from a Docker/Kubernetes secrets file, ignoring surrounding whitespace
+pub fn read_secret_file(path: &Path) -> anyhow::Result<Secret> {
+    std::fs::read_to_string(path)
+        .map(|contents| Secret::new(contents.trim()))
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+/// `DISCORD_TOKEN` from the `.env` file
+fn read_dotenv_token() -> Option<Secret> {
+    dotenvy::dotenv_iter()
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|(key, _)| key == "DISCORD_TOKEN")
+        .map(|(_, value)| Secret::new(value.trim()))
+}
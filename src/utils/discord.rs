@@ -3,44 +3,153 @@ use reqwest::header::{
     HeaderMap, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CONTENT_TYPE, USER_AGENT,
 };
 use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::{error::ApiError, secret::Secret};
+
+/// Build the HTTP client shared by every upstream request
+pub fn build_http_client(
+    connect_timeout: Duration,
+    timeout: Duration,
+) -> Result<reqwest::Client, ApiError> {
+    reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(timeout)
+        .build()
+        .map_err(|e| ApiError::DiscordApiError(format!("Failed to build client: {}", e)))
+}
 
-use super::error::ApiError;
+/// Discord's quests endpoint, used unless `DISCORD_API_URL` overrides it
+pub const DEFAULT_DISCORD_API_URL: &str = "https://discord.com/api/v10/quests/@me";
+
+/// Raw upstream response, kept around so it can be archived before parsing
+#[derive(Debug, Clone)]
+pub struct UpstreamResponse {
+    pub status: u16,
+    pub body: Value,
+    pub byte_size: usize,
+    /// How long upstream asked us to wait, from a 429's headers or body
+    pub retry_after: Option<Duration>,
+}
 
-const DISCORD_API_URL: &str = "https://discord.com/api/v10/quests/@me";
+impl UpstreamResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Upstream rejected our credential
+    pub fn is_auth_failure(&self) -> bool {
+        self.status == 401
+    }
+}
 
-pub async fn fetch_discord_quests(token: &str) -> Result<Value, ApiError> {
-    let headers = generate_headers(token);
+/// Fetch the quests endpoint and return whatever Discord answered
+///
+/// Only transport failures are errors here; non-2xx responses are returned so
+/// callers can archive them. Bodies that are not JSON are kept as a JSON string.
+pub async fn fetch_discord_payload(
+    client: &reqwest::Client,
+    api_url: &str,
+    token: &Secret,
+) -> Result<UpstreamResponse, ApiError> {
+    let headers = generate_headers(token)?;
 
-    let client = reqwest::Client::new();
     let response = client
-        .get(DISCORD_API_URL)
+        .get(api_url)
         .headers(headers)
         .send()
         .await
         .map_err(|e| ApiError::DiscordApiError(format!("Request failed: {}", e)))?;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(ApiError::DiscordApiError(format!(
-            "Discord API returned {}: {}",
-            status, error_text
-        )));
-    }
+    read_upstream_response(response).await
+}
 
-    let data: Value = response
-        .json()
+/// Read a response body, keeping non-JSON error bodies as a JSON string
+pub async fn read_upstream_response(
+    response: reqwest::Response,
+) -> Result<UpstreamResponse, ApiError> {
+    let status = response.status().as_u16();
+    let retry_after_header = ["retry-after", "x-ratelimit-reset-after"]
+        .iter()
+        .find_map(|name| {
+            response
+                .headers()
+                .get(*name)?
+                .to_str()
+                .ok()?
+                .parse::<f64>()
+                .ok()
+        });
+    let bytes = response
+        .bytes()
         .await
-        .map_err(|e| ApiError::DiscordApiError(format!("Failed to parse response: {}", e)))?;
+        .map_err(|e| ApiError::DiscordApiError(format!("Failed to read response: {}", e)))?;
+
+    let body = match serde_json::from_slice(&bytes) {
+        Ok(body) => body,
+        Err(_) if !(200..300).contains(&status) => {
+            Value::String(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        Err(e) => {
+            return Err(ApiError::DiscordApiError(format!(
+                "Failed to parse response: {}",
+                e
+            )))
+        }
+    };
+
+    let retry_after = if status == 429 {
+        retry_after_header
+            .or_else(|| body.get("retry_after").and_then(Value::as_f64))
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            // Too large for a Duration; the guard clamps the pause anyway
+            .map(|secs| Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX))
+    } else {
+        None
+    };
+
+    Ok(UpstreamResponse {
+        status,
+        body,
+        byte_size: bytes.len(),
+        retry_after,
+    })
+}
 
-    // Return the full response object (includes quests and excluded_quests)
-    Ok(data)
+/// Turn a non-2xx upstream response into an error
+pub fn ensure_success(response: &UpstreamResponse) -> Result<(), ApiError> {
+    if response.is_success() {
+        return Ok(());
+    }
+
+    let error_text = match &response.body {
+        Value::String(text) if !text.is_empty() => text.clone(),
+        Value::String(_) => "Unknown error".to_string(),
+        other => other.to_string(),
+    };
+
+    let status = reqwest::StatusCode::from_u16(response.status)
+        .map(|status| status.to_string())
+        .unwrap_or_else(|_| response.status.to_string());
+
+    let message = format!("Discord API returned {}: {}", status, error_text);
+
+    if response.is_auth_failure() {
+        return Err(ApiError::UpstreamAuthError(message));
+    }
+
+    Err(ApiError::DiscordApiError(message))
 }
 
-fn generate_headers(token: &str) -> HeaderMap {
+fn generate_headers(token: &Secret) -> Result<HeaderMap, ApiError> {
+    // Deliberately not mentioning the value: the error ends up in logs
+    let mut authorization = HeaderValue::from_str(token.expose().trim()).map_err(|_| {
+        ApiError::ConfigError(
+            "DISCORD_TOKEN contains characters that are not valid in an HTTP header".to_string(),
+        )
+    })?;
+    authorization.set_sensitive(true);
+
     let mut headers = HeaderMap::new();
 
     headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
@@ -50,10 +159,7 @@ fn generate_headers(token: &str) -> HeaderMap {
         HeaderValue::from_static("en,en-US;q=0.9,ar;q=0.8"),
     );
 
-    headers.insert(
-        AUTHORIZATION,
-        HeaderValue::from_str(token.trim()).expect("Invalid token"),
-    );
+    headers.insert(AUTHORIZATION, authorization);
 
     headers.insert("priority", HeaderValue::from_static("u=1, i"));
 
@@ -88,7 +194,7 @@ fn generate_headers(token: &str) -> HeaderMap {
 
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
-    headers
+    Ok(headers)
 }
 
 fn generate_super_properties() -> String {
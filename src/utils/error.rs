@@ -6,7 +6,7 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum ApiError {
     #[error("Database error: {0}")]
     DatabaseError(String),
@@ -14,7 +14,12 @@ pub enum ApiError {
     #[error("Discord API error: {0}")]
     DiscordApiError(String),
 
-    #[allow(dead_code)]
+    #[error("Authentication error: {0}")]
+    AuthError(String),
+
+    #[error("Upstream authentication error: {0}")]
+    UpstreamAuthError(String),
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
@@ -22,6 +27,15 @@ pub enum ApiError {
     #[error("Cache error: {0}")]
     CacheError(String),
 
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Service unavailable: {0}")]
+    Unavailable(String),
+
     #[allow(dead_code)]
     #[error("Internal server error: {0}")]
     InternalError(String),
@@ -38,6 +52,14 @@ impl IntoResponse for ApiError {
                 tracing::error!("Discord API error: {}", msg);
                 (StatusCode::BAD_GATEWAY, msg)
             }
+            ApiError::AuthError(msg) => {
+                tracing::warn!("Authentication error: {}", msg);
+                (StatusCode::UNAUTHORIZED, msg)
+            }
+            ApiError::UpstreamAuthError(msg) => {
+                tracing::error!("Upstream authentication error: {}", msg);
+                (StatusCode::BAD_GATEWAY, msg)
+            }
             ApiError::ConfigError(msg) => {
                 tracing::error!("Configuration error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, msg)
@@ -46,6 +68,18 @@ impl IntoResponse for ApiError {
                 tracing::error!("Cache error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, msg)
             }
+            ApiError::BadRequest(msg) => {
+                tracing::debug!("Bad request: {}", msg);
+                (StatusCode::BAD_REQUEST, msg)
+            }
+            ApiError::NotFound(msg) => {
+                tracing::debug!("Not found: {}", msg);
+                (StatusCode::NOT_FOUND, msg)
+            }
+            ApiError::Unavailable(msg) => {
+                tracing::warn!("Service unavailable: {}", msg);
+                (StatusCode::SERVICE_UNAVAILABLE, msg)
+            }
             ApiError::InternalError(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, msg)
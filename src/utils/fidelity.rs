@@ -0,0 +1,130 @@
+//! The fidelity contract between ingest and reconstruction.
+//!
+//! Reconstructing a stored quest must give back the object Discord sent,
+//! except for these allowed differences:
+//!
+//! - `user_status` is per-user progress that we do not store; it is always
+//!   reconstructed as `null`
+//! - Timestamps are normalized to UTC with second precision and a `+00:00`
+//!   offset, because MySQL `TIMESTAMP` columns store nothing more
+//!
+//! Fields we do not model survive through `Quest::extra`, and keys that we
+//! would emit but Discord left out are listed in `Quest::absent_fields` and
+//! removed again. Any other difference is a bug in the quest model; the golden
+//! tests in `tests/fidelity.rs` check captured payloads against this contract.
+
+use chrono::{DateTime, SubsecRound};
+use serde_json::Value as JsonValue;
+
+use super::json_diff::{diff_json, FieldChange};
+
+/// Paths whose value may differ, with everything below them
+const ALLOWED_PATHS: [&str; 1] = ["user_status"];
+
+/// Paths holding timestamps that may differ in representation only
+const TIMESTAMP_PATHS: [&str; 3] = [
+    "config.starts_at",
+    "config.expires_at",
+    "config.rewards_config.rewards_expire_at",
+];
+
+/// Differences between an upstream quest and its reconstruction that the
+/// fidelity contract does not allow
+pub fn fidelity_violations(raw: &JsonValue, reconstructed: &JsonValue) -> Vec<FieldChange> {
+    diff_json(raw, reconstructed)
+        .into_iter()
+        .filter(|change| !is_allowed(change))
+        .collect()
+}
+
+fn is_allowed(change: &FieldChange) -> bool {
+    let under = |prefix: &str| {
+        change.path == prefix
+            || change.path.starts_with(&format!("{}.", prefix))
+            || change.path.starts_with(&format!("{}[", prefix))
+    };
+    if ALLOWED_PATHS.iter().any(|path| under(path)) {
+        return true;
+    }
+
+    TIMESTAMP_PATHS.contains(&change.path.as_str())
+        && match (&change.old, &change.new) {
+            (Some(old), Some(new)) => same_instant(old, new),
+            _ => false,
+        }
+}
+
+/// Whether two RFC 3339 strings name the same second
+fn same_instant(a: &JsonValue, b: &JsonValue) -> bool {
+    let parse = |value: &JsonValue| {
+        value
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.trunc_subsecs(0))
+    };
+
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// JSON pointers of object keys present in `reconstructed` but not in `raw`
+///
+/// Arrays are compared element by element; elements themselves are never
+/// reported, only keys inside them.
+pub fn absent_paths(raw: &JsonValue, reconstructed: &JsonValue) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_absent("", raw, reconstructed, &mut paths);
+    paths
+}
+
+fn collect_absent(pointer: &str, raw: &JsonValue, built: &JsonValue, paths: &mut Vec<String>) {
+    match (raw, built) {
+        (JsonValue::Object(raw), JsonValue::Object(built)) => {
+            for (key, built_value) in built {
+                let child = format!("{}/{}", pointer, escape(key));
+                match raw.get(key) {
+                    Some(raw_value) => collect_absent(&child, raw_value, built_value, paths),
+                    None => paths.push(child),
+                }
+            }
+        }
+        (JsonValue::Array(raw), JsonValue::Array(built)) => {
+            for (index, (raw_value, built_value)) in raw.iter().zip(built).enumerate() {
+                collect_absent(
+                    &format!("{}/{}", pointer, index),
+                    raw_value,
+                    built_value,
+                    paths,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove the keys named by JSON pointers, ignoring any that do not exist
+pub fn remove_paths(target: &mut JsonValue, paths: &[String]) {
+    for path in paths {
+        let Some((parent, key)) = path.rsplit_once('/') else {
+            continue;
+        };
+        let key = unescape(key);
+
+        match target.pointer_mut(parent) {
+            Some(JsonValue::Object(map)) => {
+                map.remove(&key);
+            }
+            _ => continue,
+        }
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(key: &str) -> String {
+    key.replace("~1", "/").replace("~0", "~")
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Kind of change observed at a single JSON path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single field-level difference between two JSON documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<JsonValue>,
+}
+
+/// Compute the leaf-level differences between `old` and `new`
+///
+/// Paths use dot notation for object keys and `[n]` for array indices,
+/// e.g. `config.rewards_config.rewards[0].orb_quantity`.
+pub fn diff_json(old: &JsonValue, new: &JsonValue) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_at("", old, new, &mut changes);
+    changes
+}
+
+fn diff_at(path: &str, old: &JsonValue, new: &JsonValue, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (JsonValue::Object(old_map), JsonValue::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = join_key(path, key);
+                match new_map.get(key) {
+                    Some(new_value) => diff_at(&child, old_value, new_value, changes),
+                    None => changes.push(removed(child, old_value)),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    changes.push(added(join_key(path, key), new_value));
+                }
+            }
+        }
+        (JsonValue::Array(old_items), JsonValue::Array(new_items)) => {
+            for (index, old_value) in old_items.iter().enumerate() {
+                let child = format!("{}[{}]", path, index);
+                match new_items.get(index) {
+                    Some(new_value) => diff_at(&child, old_value, new_value, changes),
+                    None => changes.push(removed(child, old_value)),
+                }
+            }
+            for (index, new_value) in new_items.iter().enumerate().skip(old_items.len()) {
+                changes.push(added(format!("{}[{}]", path, index), new_value));
+            }
+        }
+        _ if old == new => {}
+        _ => changes.push(FieldChange {
+            path: path.to_string(),
+            kind: ChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+fn join_key(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn added(path: String, value: &JsonValue) -> FieldChange {
+    FieldChange {
+        path,
+        kind: ChangeKind::Added,
+        old: None,
+        new: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &JsonValue) -> FieldChange {
+    FieldChange {
+        path,
+        kind: ChangeKind::Removed,
+        old: Some(value.clone()),
+        new: None,
+    }
+}
@@ -1,3 +1,18 @@
+pub mod asset_mirror;
+pub mod asset_url;
+pub mod credentials;
 pub mod discord;
 pub mod error;
+pub mod fidelity;
+pub mod json_diff;
+pub mod payload_archive;
+pub mod platform;
+pub mod quest_filter;
 pub mod quest_parser;
+pub mod refresh;
+pub mod reward_kind;
+pub mod scheduler;
+pub mod secret;
+pub mod single_flight;
+pub mod task_kind;
+pub mod upstream_guard;
@@ -0,0 +1,62 @@
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use crate::db::payload_operations::{get_latest_payload_hash, prune_payloads, upsert_payload};
+use crate::utils::discord::UpstreamResponse;
+use crate::utils::error::ApiError;
+
+/// Result of archiving an upstream response
+#[derive(Debug, Clone)]
+pub struct ArchiveOutcome {
+    pub sha256: String,
+    /// The previous fetch returned the same content and it was fully ingested
+    pub unchanged: bool,
+}
+
+/// SHA-256 of the canonical JSON encoding of a value
+///
+/// `serde_json` keeps object keys sorted, so serializing without whitespace
+/// yields the same bytes for semantically identical payloads.
+pub fn canonical_sha256(value: &JsonValue) -> String {
+    let canonical = serde_json::to_vec(value).unwrap_or_default();
+    format!("{:x}", Sha256::digest(canonical))
+}
+
+/// Archive an upstream response, deduplicating identical payloads
+///
+/// Keeps at most `retention` payloads; a retention of 0 disables archiving.
+pub async fn archive_upstream_response(
+    pool: &MySqlPool,
+    response: &UpstreamResponse,
+    retention: u64,
+) -> Result<ArchiveOutcome, ApiError> {
+    let sha256 = canonical_sha256(&response.body);
+
+    if retention == 0 {
+        return Ok(ArchiveOutcome {
+            sha256,
+            unchanged: false,
+        });
+    }
+
+    let previous = get_latest_payload_hash(pool).await?;
+    let unchanged =
+        response.is_success() && matches!(&previous, Some((hash, true)) if *hash == sha256);
+
+    upsert_payload(
+        pool,
+        &sha256,
+        response.status,
+        response.byte_size,
+        &response.body,
+    )
+    .await?;
+
+    let pruned = prune_payloads(pool, retention).await?;
+    if pruned > 0 {
+        tracing::debug!("🗑️  Pruned {} archived payload(s)", pruned);
+    }
+
+    Ok(ArchiveOutcome { sha256, unchanged })
+}
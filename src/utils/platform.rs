@@ -0,0 +1,74 @@
+use std::{fmt, str::FromStr};
+
+/// Platform a quest reward can be redeemed on (`rewards_config.platforms`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    CrossPlatform,
+    Xbox,
+    Playstation,
+    Switch,
+    Pc,
+    /// Any other platform id, named `UNKNOWN_<id>`
+    Unknown(i32),
+}
+
+impl Platform {
+    pub fn from_id(id: i32) -> Self {
+        match id {
+            0 => Platform::CrossPlatform,
+            1 => Platform::Xbox,
+            2 => Platform::Playstation,
+            3 => Platform::Switch,
+            4 => Platform::Pc,
+            other => Platform::Unknown(other),
+        }
+    }
+
+    pub fn id(self) -> i32 {
+        match self {
+            Platform::CrossPlatform => 0,
+            Platform::Xbox => 1,
+            Platform::Playstation => 2,
+            Platform::Switch => 3,
+            Platform::Pc => 4,
+            Platform::Unknown(id) => id,
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Platform::CrossPlatform => f.write_str("CROSS_PLATFORM"),
+            Platform::Xbox => f.write_str("XBOX"),
+            Platform::Playstation => f.write_str("PLAYSTATION"),
+            Platform::Switch => f.write_str("SWITCH"),
+            Platform::Pc => f.write_str("PC"),
+            Platform::Unknown(id) => write!(f, "UNKNOWN_{}", id),
+        }
+    }
+}
+
+/// Accepts a name in any case (`xbox`, `cross_platform`) or a numeric id
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(id) = s.parse::<i32>() {
+            return Ok(Platform::from_id(id));
+        }
+
+        match s.to_ascii_uppercase().replace('-', "_").as_str() {
+            "CROSS_PLATFORM" => Ok(Platform::CrossPlatform),
+            "XBOX" => Ok(Platform::Xbox),
+            "PLAYSTATION" => Ok(Platform::Playstation),
+            "SWITCH" => Ok(Platform::Switch),
+            "PC" => Ok(Platform::Pc),
+            _ => Err(format!(
+                "Unknown platform '{}' (expected cross_platform, xbox, playstation, switch, pc or a numeric id)",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Filters of `GET /v1/quests`. They are evaluated in SQL, and every
+//! combination is cached under its own key next to the unfiltered list, up to
+//! `CACHE_MAX_VARIANTS` combinations.
+
+use std::str::FromStr;
+
+use serde_json::json;
+
+use crate::utils::{
+    payload_archive::canonical_sha256, platform::Platform, refresh::QUEST_CACHE_KEY,
+    reward_kind::RewardCategory,
+};
+
+/// Where a quest is in its lifetime, relative to now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuestStatus {
+    /// Started and not yet expired
+    Active,
+    /// Not started yet
+    Upcoming,
+    Expired,
+}
+
+impl QuestStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuestStatus::Active => "active",
+            QuestStatus::Upcoming => "upcoming",
+            QuestStatus::Expired => "expired",
+        }
+    }
+}
+
+impl FromStr for QuestStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "active" => Ok(QuestStatus::Active),
+            "upcoming" => Ok(QuestStatus::Upcoming),
+            "expired" => Ok(QuestStatus::Expired),
+            other => Err(format!(
+                "Unknown status '{}' (expected active, upcoming or expired)",
+                other
+            )),
+        }
+    }
+}
+
+/// Conditions a quest must meet to be listed; the default matches every quest
+/// within `QUEST_AGE_DAYS`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuestFilter {
+    pub status: Option<QuestStatus>,
+    pub application_id: Option<String>,
+    /// Game publisher, compared case-insensitively
+    pub publisher: Option<String>,
+    /// Task kind such as `PLAY_ON_DESKTOP`
+    pub task_type: Option<String>,
+    pub reward_type: Option<RewardCategory>,
+    /// Redeemable on this platform; cross-platform quests always match
+    pub platform: Option<Platform>,
+    /// At least one reward worth this many orbs or more
+    pub min_orbs: Option<i32>,
+    pub preview: Option<bool>,
+    /// Overrides `QUEST_AGE_DAYS` for this request
+    pub age_days: Option<i64>,
+}
+
+impl QuestFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Cache key of the list this filter selects
+    ///
+    /// The unfiltered list keeps [`QUEST_CACHE_KEY`]; filtered variants use
+    /// `<QUEST_CACHE_KEY>?<hash of the filter>`.
+    pub fn cache_key(&self) -> String {
+        if self.is_empty() {
+            return QUEST_CACHE_KEY.to_string();
+        }
+
+        let canonical = json!({
+            "status": self.status.map(QuestStatus::as_str),
+            "application_id": self.application_id,
+            "publisher": self.publisher.as_deref().map(str::to_lowercase),
+            "task_type": self.task_type,
+            "reward_type": self.reward_type.map(RewardCategory::as_str),
+            "platform": self.platform.map(Platform::id),
+            "min_orbs": self.min_orbs,
+            "preview": self.preview,
+            "age_days": self.age_days,
+        });
+
+        format!("{}?{}", QUEST_CACHE_KEY, canonical_sha256(&canonical))
+    }
+}
+
+/// `true`/`false` (or `1`/`0`) query values
+pub fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!(
+            "Invalid {} '{}' (expected true or false)",
+            name, value
+        )),
+    }
+}
+
+/// Non-negative integer query values
+pub fn parse_count<T: FromStr + Default + PartialOrd>(
+    name: &str,
+    value: &str,
+) -> Result<T, String> {
+    value
+        .trim()
+        .parse::<T>()
+        .ok()
+        .filter(|count| *count >= T::default())
+        .ok_or_else(|| {
+            format!(
+                "Invalid {} '{}' (expected a non-negative number)",
+                name, value
+            )
+        })
+}
@@ -1,27 +1,54 @@
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{json, Value as JsonValue};
-use sqlx::MySqlPool;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::{BTreeMap, HashMap};
 
+use crate::db::drift_operations::upsert_schema_drift;
 use crate::db::quest_models::*;
 use crate::db::quest_operations::*;
 use crate::utils::error::ApiError;
+use crate::utils::fidelity::{absent_paths, remove_paths};
+use crate::utils::json_diff::{diff_json, FieldChange};
+use crate::utils::platform::Platform;
+use crate::utils::quest_filter::QuestFilter;
+use crate::utils::reward_kind::RewardSummary;
+use crate::utils::task_kind::TaskSummary;
 
-/// Discord API quest response structures (for parsing)
-#[derive(Debug, Deserialize)]
-pub struct DiscordQuestResponse {
-    pub quests: Vec<DiscordQuest>,
-}
+/// Unknown keys collected from a Discord object
+pub type ExtraFields = serde_json::Map<String, JsonValue>;
 
+/// Discord API quest response structures (for parsing)
+///
+/// The response is an object with `quests` and `excluded_quests` arrays; each
+/// entry is parsed on its own so one malformed quest cannot fail the batch.
 #[derive(Debug, Deserialize)]
 pub struct DiscordQuest {
     pub id: String,
     pub config: QuestConfig,
     pub preview: bool,
+    /// Per-user progress; we only track quest configuration, so this is ignored
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub user_status: Option<JsonValue>,
+    /// Fields we do not model yet, kept so they survive a round-trip
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Entry of `excluded_quests`: a quest Discord withdrew, possibly for a successor
+#[derive(Debug, Deserialize)]
+pub struct DiscordExcludedQuest {
+    pub id: String,
+    pub replacement_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct QuestConfig {
+    /// Always the quest id; rebuilt from it on reconstruction
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub id: Option<String>,
     pub config_version: i32,
     pub starts_at: String,
     pub expires_at: String,
@@ -30,10 +57,15 @@ pub struct QuestConfig {
     pub assets: Assets,
     pub colors: Colors,
     pub messages: Messages,
+    /// Legacy (v1) task layout; only used when `task_config_v2` is absent,
+    /// otherwise kept verbatim
+    pub task_config: Option<JsonValue>,
     pub task_config_v2: Option<TaskConfigV2>,
     pub rewards_config: RewardsConfig,
     pub share_policy: String,
     pub cta_config: Option<CtaConfig>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -41,6 +73,8 @@ pub struct Application {
     pub id: String,
     pub name: String,
     pub link: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,12 +89,16 @@ pub struct Assets {
     pub game_tile_dark: Option<String>,
     pub logotype_light: Option<String>,
     pub logotype_dark: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Colors {
     pub primary: String,
     pub secondary: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,12 +106,69 @@ pub struct Messages {
     pub quest_name: String,
     pub game_title: String,
     pub game_publisher: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TaskConfigV2 {
-    pub tasks: serde_json::Map<String, JsonValue>,
+    /// Keyed by task kind, e.g. `WATCH_VIDEO`
+    pub tasks: BTreeMap<String, TaskEntry<DiscordTask>>,
+    pub join_operator: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Legacy (v1) task configuration, used by older quests
+#[derive(Debug, Deserialize)]
+pub struct TaskConfigV1 {
+    /// Keyed by task kind, e.g. `PLAY_ON_DESKTOP`
+    pub tasks: BTreeMap<String, TaskEntry<DiscordTaskV1>>,
     pub join_operator: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// A task as received: modelled when it has the expected shape, otherwise kept
+/// verbatim so one odd task cannot fail its whole quest
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum TaskEntry<T> {
+    Task(T),
+    Unparsed(JsonValue),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordTaskV1 {
+    /// Repeats the key in `tasks`; rebuilt from it on reconstruction
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub event_name: Option<String>,
+    /// Any JSON value, see [`split_target`]
+    #[serde(default, deserialize_with = "present")]
+    pub target: Option<JsonValue>,
+    pub applications: Option<JsonValue>,
+    pub external_ids: Option<JsonValue>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordTask {
+    /// Repeats the key in `tasks`; rebuilt from it on reconstruction
+    #[allow(dead_code)]
+    #[serde(rename = "type", default)]
+    pub task_type: Option<String>,
+    /// Seconds for every known task kind; any JSON value, see [`split_target`]
+    #[serde(default, deserialize_with = "present")]
+    pub target: Option<JsonValue>,
+    pub applications: Option<JsonValue>,
+    pub external_ids: Option<JsonValue>,
+    /// Video and other media of the task
+    pub assets: Option<JsonValue>,
+    pub messages: Option<JsonValue>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -82,6 +177,8 @@ pub struct RewardsConfig {
     pub rewards: Vec<Reward>,
     pub rewards_expire_at: Option<String>,
     pub platforms: Vec<i32>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -91,6 +188,8 @@ pub struct Reward {
     pub sku_id: Option<String>,
     pub messages: RewardMessages,
     pub orb_quantity: Option<i32>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
@@ -98,21 +197,119 @@ pub struct RewardMessages {
     pub name: String,
     pub name_with_article: String,
     pub redemption_instructions_by_platform: Option<serde_json::Map<String, JsonValue>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CtaConfig {
     pub link: String,
     pub button_label: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Counts reported after ingesting a Discord response
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct IngestSummary {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub excluded: usize,
+    pub failed: usize,
 }
 
-// Parse and save ONLY NEW Discord quests to database (optimization)
+impl IngestSummary {
+    /// Number of quests that were written to the database
+    pub fn written(&self) -> usize {
+        self.inserted + self.updated
+    }
+}
+
+/// What ingest will do with a single incoming quest
+#[derive(Debug)]
+pub enum QuestAction {
+    Insert,
+    Update {
+        stored: Box<CompleteQuest>,
+        stored_snapshot: JsonValue,
+        changes: Vec<FieldChange>,
+    },
+    Unchanged,
+}
+
+/// An incoming quest together with the action planned for it
+#[derive(Debug)]
+pub struct PlannedQuest {
+    pub quest: CompleteQuest,
+    pub snapshot: JsonValue,
+    pub action: QuestAction,
+    pub unknown_fields: Vec<UnknownField>,
+    pub raw: JsonValue,
+}
+
+/// A quest (or exclusion entry) that could not be ingested
+#[derive(Debug, Clone)]
+pub struct IngestFailure {
+    pub quest_id: Option<String>,
+    pub stage: &'static str,
+    pub error: String,
+    pub raw: JsonValue,
+}
+
+/// Everything ingest would write for one Discord response
+#[derive(Debug)]
+pub struct IngestPlan {
+    pub quests: Vec<PlannedQuest>,
+    pub excluded_quests: Vec<DiscordExcludedQuest>,
+    pub failures: Vec<IngestFailure>,
+}
+
+impl IngestPlan {
+    pub fn summary(&self) -> IngestSummary {
+        let mut summary = IngestSummary {
+            excluded: self.excluded_quests.len(),
+            failed: self.failures.len(),
+            ..Default::default()
+        };
+
+        for planned in &self.quests {
+            match planned.action {
+                QuestAction::Insert => summary.inserted += 1,
+                QuestAction::Update { .. } => summary.updated += 1,
+                QuestAction::Unchanged => summary.unchanged += 1,
+            }
+        }
+
+        summary
+    }
+}
+
+// Parse Discord quests and save new or changed ones to database
 pub async fn save_discord_quests_to_db(
     pool: &MySqlPool,
     response: &JsonValue,
-) -> Result<usize, ApiError> {
-    let quest_response: DiscordQuestResponse = serde_json::from_value(response.clone())
-        .map_err(|e| ApiError::InternalError(format!("Failed to parse Discord response: {}", e)))?;
+) -> Result<IngestSummary, ApiError> {
+    let plan = plan_discord_quests(pool, response).await?;
+    apply_ingest_plan(pool, &plan).await
+}
+
+/// Compare a Discord response with the database without writing anything
+///
+/// Each quest is parsed on its own; malformed quests end up in
+/// `IngestPlan::failures` instead of failing the whole batch.
+pub async fn plan_discord_quests(
+    pool: &MySqlPool,
+    response: &JsonValue,
+) -> Result<IngestPlan, ApiError> {
+    let raw_quests = response
+        .get("quests")
+        .and_then(JsonValue::as_array)
+        .ok_or_else(|| {
+            ApiError::InternalError(
+                "Failed to parse Discord response: missing `quests` array".to_string(),
+            )
+        })?;
 
     // Get existing quest IDs from database
     let existing_ids: std::collections::HashSet<String> =
@@ -121,26 +318,264 @@ pub async fn save_discord_quests_to_db(
             .into_iter()
             .collect();
 
-    // Filter to only new quests (not in database)
-    let new_quests: Vec<_> = quest_response
-        .quests
-        .into_iter()
-        .filter(|q| !existing_ids.contains(&q.id))
-        .collect();
+    let mut quests = Vec::new();
+    let mut failures = Vec::new();
+
+    for raw in raw_quests {
+        let (quest, unknown_fields) = match parse_single_quest(raw) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                failures.push(IngestFailure {
+                    quest_id: raw_id(raw),
+                    stage: "parse",
+                    error,
+                    raw: raw.clone(),
+                });
+                continue;
+            }
+        };
+        let snapshot = reconstruct_single_quest(&quest);
+
+        // Compare against the stored version and only rewrite when something changed
+        let stored = if existing_ids.contains(&quest.quest.id) {
+            get_complete_quest_by_id(pool, &quest.quest.id).await?
+        } else {
+            None
+        };
+
+        let action = match stored {
+            None => QuestAction::Insert,
+            Some(stored) => {
+                let stored_snapshot = reconstruct_single_quest(&stored);
+                let changes = diff_json(&stored_snapshot, &snapshot);
+                if changes.is_empty() {
+                    QuestAction::Unchanged
+                } else {
+                    QuestAction::Update {
+                        stored: Box::new(stored),
+                        stored_snapshot,
+                        changes,
+                    }
+                }
+            }
+        };
 
-    let new_count = new_quests.len();
+        quests.push(PlannedQuest {
+            quest,
+            snapshot,
+            action,
+            unknown_fields,
+            raw: raw.clone(),
+        });
+    }
 
-    // Only insert new quests
-    for quest_data in new_quests {
-        save_single_quest(pool, &quest_data).await?;
+    let mut excluded_quests = Vec::new();
+    let raw_excluded = response
+        .get("excluded_quests")
+        .and_then(JsonValue::as_array);
+    for raw in raw_excluded.into_iter().flatten() {
+        match serde_json::from_value::<DiscordExcludedQuest>(raw.clone()) {
+            Ok(excluded) => excluded_quests.push(excluded),
+            Err(e) => failures.push(IngestFailure {
+                quest_id: raw_id(raw),
+                stage: "parse_excluded",
+                error: e.to_string(),
+                raw: raw.clone(),
+            }),
+        }
     }
 
-    Ok(new_count)
+    Ok(IngestPlan {
+        quests,
+        excluded_quests,
+        failures,
+    })
 }
 
-/// Save a single quest with all related data
-async fn save_single_quest(pool: &MySqlPool, quest_data: &DiscordQuest) -> Result<(), ApiError> {
+/// Parse and normalize one raw quest object
+///
+/// Keys the reconstruction would add that `raw` does not have are recorded in
+/// `Quest::absent_fields`, see [`crate::utils::fidelity`].
+pub fn parse_single_quest(raw: &JsonValue) -> Result<(CompleteQuest, Vec<UnknownField>), String> {
+    let quest_data: DiscordQuest =
+        serde_json::from_value(raw.clone()).map_err(|e| e.to_string())?;
+    let (mut quest, unknown_fields) =
+        build_complete_quest(&quest_data).map_err(|e| e.to_string())?;
+
+    let absent = absent_paths(raw, &reconstruct_single_quest(&quest));
+    if !absent.is_empty() {
+        quest.quest.absent_fields = Some(json!(absent));
+    }
+
+    Ok((quest, unknown_fields))
+}
+
+fn raw_id(raw: &JsonValue) -> Option<String> {
+    raw.get("id")
+        .and_then(JsonValue::as_str)
+        .map(str::to_string)
+}
+
+/// Write the inserts, updates and exclusions of a plan to the database
+///
+/// A quest that fails to save is quarantined and the rest of the batch continues.
+pub async fn apply_ingest_plan(
+    pool: &MySqlPool,
+    plan: &IngestPlan,
+) -> Result<IngestSummary, ApiError> {
+    let mut summary = IngestSummary {
+        failed: plan.failures.len(),
+        ..Default::default()
+    };
+
+    for failure in &plan.failures {
+        quarantine(pool, failure).await?;
+    }
+
+    for planned in &plan.quests {
+        match apply_planned_quest(pool, planned).await {
+            Ok(()) => match planned.action {
+                QuestAction::Insert => summary.inserted += 1,
+                QuestAction::Update { .. } => summary.updated += 1,
+                QuestAction::Unchanged => summary.unchanged += 1,
+            },
+            Err(e) => {
+                let failure = IngestFailure {
+                    quest_id: Some(planned.quest.quest.id.clone()),
+                    stage: "save",
+                    error: e.to_string(),
+                    raw: planned.raw.clone(),
+                };
+                quarantine(pool, &failure).await?;
+                summary.failed += 1;
+            }
+        }
+    }
+
+    // Track quests Discord reports as excluded (and their replacements)
+    for excluded in &plan.excluded_quests {
+        upsert_excluded_quest(pool, &excluded.id, excluded.replacement_id.as_deref()).await?;
+        summary.excluded += 1;
+    }
+
+    if summary.failed > 0 {
+        tracing::warn!(
+            "⚠️  {} quest(s) failed to ingest and were quarantined",
+            summary.failed
+        );
+    }
+
+    Ok(summary)
+}
+
+async fn apply_planned_quest(pool: &MySqlPool, planned: &PlannedQuest) -> Result<(), ApiError> {
+    record_unknown_fields(pool, &planned.quest.quest.id, &planned.unknown_fields).await?;
+
+    if matches!(planned.action, QuestAction::Unchanged) {
+        return Ok(());
+    }
+
+    // The quest and its revisions are written together: if a revision fails the
+    // quest keeps its old state, so the next ingest sees the change again
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    // Saving first locks the quest row, so concurrent ingests of the same quest
+    // number their revisions one after the other
+    save_single_quest(&mut tx, &planned.quest).await?;
+
+    match &planned.action {
+        QuestAction::Unchanged => {}
+        QuestAction::Insert => {
+            record_revision(&mut tx, &planned.quest, &planned.snapshot, &[]).await?;
+        }
+        QuestAction::Update {
+            stored,
+            stored_snapshot,
+            changes,
+        } => {
+            // Quests stored before revisions were tracked get their old state as a baseline
+            if !has_quest_revisions(&mut tx, &stored.quest.id).await? {
+                record_revision(&mut tx, stored, stored_snapshot, &[]).await?;
+            }
+
+            record_revision(&mut tx, &planned.quest, &planned.snapshot, changes).await?;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Write a failed quest to the quarantine table
+async fn quarantine(pool: &MySqlPool, failure: &IngestFailure) -> Result<(), ApiError> {
+    tracing::warn!(
+        "🚧 Quarantined quest {} ({}): {}",
+        failure.quest_id.as_deref().unwrap_or("<unknown id>"),
+        failure.stage,
+        failure.error
+    );
+
+    insert_ingest_failure(
+        pool,
+        failure.quest_id.as_deref(),
+        failure.stage,
+        &failure.error,
+        &failure.raw,
+    )
+    .await
+}
+
+/// Log unknown upstream fields to the schema drift table, warning on new paths
+async fn record_unknown_fields(
+    pool: &MySqlPool,
+    quest_id: &str,
+    fields: &[UnknownField],
+) -> Result<(), ApiError> {
+    for field in fields {
+        if upsert_schema_drift(pool, &field.path, &field.example, quest_id).await? {
+            tracing::warn!(
+                "🆕 New upstream field observed: {} (quest {})",
+                field.path,
+                quest_id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Store a snapshot of a quest together with the changes that produced it
+async fn record_revision(
+    conn: &mut MySqlConnection,
+    cq: &CompleteQuest,
+    snapshot: &JsonValue,
+    changes: &[FieldChange],
+) -> Result<(), ApiError> {
+    insert_quest_revision(
+        conn,
+        &cq.quest.id,
+        cq.quest.config_version,
+        snapshot,
+        &json!(changes),
+    )
+    .await
+}
+
+/// Convert a parsed Discord quest into the normalized database model
+///
+/// Also returns the unknown fields that were folded into `Quest::extra`.
+fn build_complete_quest(
+    quest_data: &DiscordQuest,
+) -> Result<(CompleteQuest, Vec<UnknownField>), ApiError> {
     let config = &quest_data.config;
+    let legacy_tasks = legacy_task_config(config)?;
+    let (extra, unknown_fields) = collect_unknown_fields(quest_data, legacy_tasks.as_ref());
 
     // Parse timestamps
     let starts_at = parse_timestamp(&config.starts_at)?;
@@ -170,130 +605,621 @@ async fn save_single_quest(pool: &MySqlPool, quest_data: &DiscordQuest) -> Resul
         game_publisher: config.messages.game_publisher.clone(),
         cta_link: config.cta_config.as_ref().map(|c| c.link.clone()),
         cta_button_label: config.cta_config.as_ref().map(|c| c.button_label.clone()),
-        task_join_operator: config
-            .task_config_v2
-            .as_ref()
-            .map(|t| t.join_operator.clone())
-            .unwrap_or_else(|| "or".to_string()),
+        task_join_operator: match (&config.task_config_v2, &legacy_tasks) {
+            (Some(v2), _) => v2.join_operator.clone(),
+            (None, Some(v1)) => v1.join_operator.clone(),
+            (None, None) => "or".to_string(),
+        },
+        task_config_version: match (&config.task_config_v2, &legacy_tasks) {
+            (Some(_), _) => Some(2),
+            (None, Some(_)) => Some(1),
+            (None, None) => None,
+        },
         reward_assignment_method: config.rewards_config.assignment_method,
         rewards_expire_at,
+        extra,
+        absent_fields: None,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     };
 
-    // Save main quest
-    upsert_quest(pool, &quest).await?;
-
-    // Save assets
+    // Assets
     let assets = &config.assets;
-    upsert_quest_assets(
-        pool,
-        &quest_data.id,
-        assets.hero.as_deref(),
-        assets.hero_video.as_deref(),
-        assets.quest_bar_hero.as_deref(),
-        assets.quest_bar_hero_video.as_deref(),
-        assets.game_tile.as_deref(),
-        assets.logotype.as_deref(),
-        assets.game_tile_light.as_deref(),
-        assets.game_tile_dark.as_deref(),
-        assets.logotype_light.as_deref(),
-        assets.logotype_dark.as_deref(),
-    )
-    .await?;
+    let assets = QuestAssets {
+        id: 0,
+        quest_id: quest_data.id.clone(),
+        hero: assets.hero.clone(),
+        hero_video: assets.hero_video.clone(),
+        quest_bar_hero: assets.quest_bar_hero.clone(),
+        quest_bar_hero_video: assets.quest_bar_hero_video.clone(),
+        game_tile: assets.game_tile.clone(),
+        logotype: assets.logotype.clone(),
+        game_tile_light: assets.game_tile_light.clone(),
+        game_tile_dark: assets.game_tile_dark.clone(),
+        logotype_light: assets.logotype_light.clone(),
+        logotype_dark: assets.logotype_dark.clone(),
+    };
 
-    // Save tasks
+    // Tasks
+    let mut tasks = Vec::new();
     if let Some(task_config) = &config.task_config_v2 {
-        let mut tasks = Vec::new();
-        for (task_type, task_data) in &task_config.tasks {
-            let target = task_data["target"].as_i64().unwrap_or(0) as i32;
-            let applications = task_data.get("applications").cloned();
-            let external_ids = task_data.get("external_ids").cloned();
-            tasks.push((task_type.clone(), target, applications, external_ids));
+        for (task_type, entry) in &task_config.tasks {
+            // Unparsed tasks live in `Quest::extra` only
+            let TaskEntry::Task(task) = entry else {
+                continue;
+            };
+            tasks.push(QuestTask {
+                id: 0,
+                quest_id: quest_data.id.clone(),
+                task_type: task_type.clone(),
+                target: split_target(task.target.as_ref()).0,
+                applications: task.applications.clone(),
+                external_ids: task.external_ids.clone(),
+                assets: task.assets.clone(),
+                messages: task.messages.clone(),
+            });
+        }
+    } else if let Some(task_config) = &legacy_tasks {
+        for (task_type, entry) in &task_config.tasks {
+            let TaskEntry::Task(task) = entry else {
+                continue;
+            };
+            tasks.push(QuestTask {
+                id: 0,
+                quest_id: quest_data.id.clone(),
+                task_type: task_type.clone(),
+                target: split_target(task.target.as_ref()).0,
+                applications: task.applications.clone(),
+                external_ids: task.external_ids.clone(),
+                assets: None,
+                messages: None,
+            });
         }
-        replace_quest_tasks(pool, &quest_data.id, &tasks).await?;
-    }
-
-    // Save rewards
-    let mut rewards = Vec::new();
-    for reward in &config.rewards_config.rewards {
-        let platform = config
-            .rewards_config
-            .platforms
-            .first()
-            .copied()
-            .unwrap_or(0);
-        let redemption_instructions = reward
-            .messages
-            .redemption_instructions_by_platform
-            .as_ref()
-            .map(|m| json!(m));
-
-        rewards.push((
-            reward.reward_type,
-            reward.sku_id.clone(),
-            reward.messages.name.clone(),
-            reward.messages.name_with_article.clone(),
-            reward.orb_quantity,
-            redemption_instructions,
+    }
+
+    // Rewards
+    let rewards = config
+        .rewards_config
+        .rewards
+        .iter()
+        .map(|reward| QuestReward {
+            id: 0,
+            quest_id: quest_data.id.clone(),
+            reward_type: reward.reward_type,
+            sku_id: reward.sku_id.clone(),
+            reward_name: reward.messages.name.clone(),
+            reward_name_with_article: reward.messages.name_with_article.clone(),
+            orb_quantity: reward.orb_quantity,
+            redemption_instructions: reward
+                .messages
+                .redemption_instructions_by_platform
+                .as_ref()
+                .map(|m| json!(m)),
+        })
+        .collect();
+
+    // Platforms apply to all rewards of the quest
+    let platforms = config
+        .rewards_config
+        .platforms
+        .iter()
+        .map(|&platform| QuestPlatform {
+            id: 0,
+            quest_id: quest_data.id.clone(),
             platform,
-        ));
+        })
+        .collect();
+
+    // Features
+    let features = config
+        .features
+        .iter()
+        .flatten()
+        .map(|&feature_id| QuestFeature {
+            id: 0,
+            quest_id: quest_data.id.clone(),
+            feature_id,
+        })
+        .collect();
+
+    // Skip user status - we only track quest configuration, not user progress
+
+    let complete_quest = CompleteQuest {
+        quest,
+        assets: Some(assets),
+        tasks,
+        rewards,
+        features,
+        platforms,
+        user_statuses: Vec::new(),
+    };
+
+    Ok((complete_quest, unknown_fields))
+}
+
+/// The v1 `task_config`, when it is the one tasks are read from
+fn legacy_task_config(config: &QuestConfig) -> Result<Option<TaskConfigV1>, ApiError> {
+    match (&config.task_config_v2, &config.task_config) {
+        (None, Some(raw)) => serde_json::from_value(raw.clone())
+            .map(Some)
+            .map_err(|e| ApiError::InternalError(format!("Failed to parse task_config: {}", e))),
+        _ => Ok(None),
     }
-    replace_quest_rewards(pool, &quest_data.id, &rewards).await?;
+}
+
+/// Keep an explicit `null` apart from a missing key, which `Option` alone would merge
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<JsonValue>, D::Error> {
+    JsonValue::deserialize(deserializer).map(Some)
+}
+
+/// The `target` column value of a task, plus the received value when the column
+/// cannot hold it exactly (a fraction, out of range, not a number)
+///
+/// The received value is kept in `Quest::extra`, so it reconstructs unchanged.
+fn split_target(target: Option<&JsonValue>) -> (i32, Option<JsonValue>) {
+    let Some(value) = target else {
+        return (0, None);
+    };
 
-    // Save features
-    if let Some(features) = &config.features {
-        replace_quest_features(pool, &quest_data.id, features).await?;
+    match value.as_i64().and_then(|target| i32::try_from(target).ok()) {
+        Some(target) => (target, None),
+        None => (
+            value.as_f64().map(|target| target as i32).unwrap_or(0),
+            Some(value.clone()),
+        ),
     }
+}
 
-    // Skip user status - we only track quest configuration, not user progress
+/// An upstream field path we do not model, with the value it had
+#[derive(Debug, Clone)]
+pub struct UnknownField {
+    /// Generalized path, e.g. `config.rewards_config.rewards[].messages.foo`
+    pub path: String,
+    pub example: JsonValue,
+}
+
+/// Gather unknown keys from every level of a quest
+///
+/// The result mirrors the Discord shape (arrays keep one entry per element) so it
+/// can be merged straight back into a reconstructed quest.
+fn collect_unknown_fields(
+    quest_data: &DiscordQuest,
+    legacy_tasks: Option<&TaskConfigV1>,
+) -> (Option<JsonValue>, Vec<UnknownField>) {
+    let mut fields = std::collections::BTreeMap::new();
+    let mut take = |path: &str, extra: &ExtraFields| {
+        for (key, value) in extra {
+            fields
+                .entry(format!("{}{}", path, key))
+                .or_insert_with(|| value.clone());
+        }
+        extra.clone()
+    };
+
+    let config = &quest_data.config;
+    let mut root = take("", &quest_data.extra);
+    let mut config_extra = take("config.", &config.extra);
+
+    insert_non_empty(
+        &mut config_extra,
+        "application",
+        take("config.application.", &config.application.extra),
+    );
+    insert_non_empty(
+        &mut config_extra,
+        "assets",
+        take("config.assets.", &config.assets.extra),
+    );
+    insert_non_empty(
+        &mut config_extra,
+        "colors",
+        take("config.colors.", &config.colors.extra),
+    );
+    insert_non_empty(
+        &mut config_extra,
+        "messages",
+        take("config.messages.", &config.messages.extra),
+    );
+
+    if let Some(cta_config) = &config.cta_config {
+        insert_non_empty(
+            &mut config_extra,
+            "cta_config",
+            take("config.cta_config.", &cta_config.extra),
+        );
+    }
+
+    if let Some(task_config) = &config.task_config_v2 {
+        let mut task_config_extra = take("config.task_config_v2.", &task_config.extra);
+        let tasks_extra = collect_task_extra(
+            &mut take,
+            "config.task_config_v2.tasks.",
+            &task_config.tasks,
+            |task| (&task.extra, task.target.as_ref()),
+        );
+        insert_non_empty(&mut task_config_extra, "tasks", tasks_extra);
+        insert_non_empty(&mut config_extra, "task_config_v2", task_config_extra);
+
+        // Tasks come from v2; a v1 copy alongside it is kept as received
+        if let Some(legacy) = &config.task_config {
+            config_extra.insert("task_config".to_string(), legacy.clone());
+        }
+    }
+
+    if let Some(task_config) = legacy_tasks {
+        let mut task_config_extra = take("config.task_config.", &task_config.extra);
+        let tasks_extra = collect_task_extra(
+            &mut take,
+            "config.task_config.tasks.",
+            &task_config.tasks,
+            |task| (&task.extra, task.target.as_ref()),
+        );
+        insert_non_empty(&mut task_config_extra, "tasks", tasks_extra);
+        insert_non_empty(&mut config_extra, "task_config", task_config_extra);
+    }
+
+    let rewards_config = &config.rewards_config;
+    let mut rewards_config_extra = take("config.rewards_config.", &rewards_config.extra);
+    let rewards_extra: Vec<JsonValue> = rewards_config
+        .rewards
+        .iter()
+        .map(|reward| {
+            let mut reward_extra = take("config.rewards_config.rewards[].", &reward.extra);
+            insert_non_empty(
+                &mut reward_extra,
+                "messages",
+                take(
+                    "config.rewards_config.rewards[].messages.",
+                    &reward.messages.extra,
+                ),
+            );
+            JsonValue::Object(reward_extra)
+        })
+        .collect();
+    if rewards_extra
+        .iter()
+        .any(|r| r.as_object().is_some_and(|o| !o.is_empty()))
+    {
+        rewards_config_extra.insert("rewards".to_string(), JsonValue::Array(rewards_extra));
+    }
+    insert_non_empty(&mut config_extra, "rewards_config", rewards_config_extra);
+
+    insert_non_empty(&mut root, "config", config_extra);
+
+    let unknown_fields = fields
+        .into_iter()
+        .map(|(path, example)| UnknownField { path, example })
+        .collect();
+
+    if root.is_empty() {
+        (None, unknown_fields)
+    } else {
+        (Some(JsonValue::Object(root)), unknown_fields)
+    }
+}
+
+/// Unknown keys of every task under `path`, keyed by task kind
+///
+/// Unparsed tasks are kept whole, and a `target` the column cannot hold is
+/// kept next to the task's other unknown keys.
+fn collect_task_extra<T>(
+    take: &mut impl FnMut(&str, &ExtraFields) -> ExtraFields,
+    path: &str,
+    tasks: &BTreeMap<String, TaskEntry<T>>,
+    fields: impl Fn(&T) -> (&ExtraFields, Option<&JsonValue>),
+) -> ExtraFields {
+    let task_path = format!("{}*.", path);
+    let mut tasks_extra = ExtraFields::new();
+
+    for (task_type, entry) in tasks {
+        match entry {
+            TaskEntry::Task(task) => {
+                let (extra, target) = fields(task);
+                let mut task_extra = take(&task_path, extra);
+                if let Some(target) = split_target(target).1 {
+                    let target = ExtraFields::from_iter([("target".to_string(), target)]);
+                    task_extra.extend(take(&task_path, &target));
+                }
+                insert_non_empty(&mut tasks_extra, task_type, task_extra);
+            }
+            TaskEntry::Unparsed(raw) => {
+                let raw = ExtraFields::from_iter([(task_type.clone(), raw.clone())]);
+                tasks_extra.extend(take(path, &raw));
+            }
+        }
+    }
+
+    tasks_extra
+}
+
+fn insert_non_empty(target: &mut ExtraFields, key: &str, value: ExtraFields) {
+    if !value.is_empty() {
+        target.insert(key.to_string(), JsonValue::Object(value));
+    }
+}
+
+/// Merge stored extra fields back into a reconstructed quest
+///
+/// Objects are merged key by key and arrays element by element; anything else
+/// in `extra` replaces the reconstructed value.
+fn merge_extra(target: &mut JsonValue, extra: &JsonValue) {
+    match (target, extra) {
+        (JsonValue::Object(target), JsonValue::Object(extra)) => {
+            for (key, value) in extra {
+                match target.get_mut(key) {
+                    Some(existing) => merge_extra(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (JsonValue::Array(target), JsonValue::Array(extra)) => {
+            for (index, value) in extra.iter().enumerate() {
+                match target.get_mut(index) {
+                    Some(existing) => merge_extra(existing, value),
+                    None => target.push(value.clone()),
+                }
+            }
+        }
+        (target, extra) => *target = extra.clone(),
+    }
+}
+
+/// Save a single quest with all related data
+///
+/// Runs on the caller's transaction, so readers never see a quest whose tasks
+/// or rewards are missing.
+async fn save_single_quest(conn: &mut MySqlConnection, cq: &CompleteQuest) -> Result<(), ApiError> {
+    let quest_id = &cq.quest.id;
+
+    // Save main quest
+    upsert_quest(&mut *conn, &cq.quest).await?;
+
+    // Save assets
+    if let Some(assets) = &cq.assets {
+        upsert_quest_assets(&mut *conn, assets).await?;
+    }
+
+    // Save tasks, rewards, features and platforms
+    replace_quest_tasks(&mut *conn, quest_id, &cq.tasks).await?;
+    replace_quest_rewards(&mut *conn, quest_id, &cq.rewards).await?;
+
+    let features: Vec<i32> = cq.features.iter().map(|f| f.feature_id).collect();
+    replace_quest_features(&mut *conn, quest_id, &features).await?;
+
+    let platforms: Vec<i32> = cq.platforms.iter().map(|p| p.platform).collect();
+    replace_quest_platforms(&mut *conn, quest_id, &platforms).await?;
 
     Ok(())
 }
 
 /// Parse timestamp string to DateTime<Utc>
+///
+/// Sub-second precision is dropped because MySQL `TIMESTAMP` columns cannot
+/// store it; keeping it would make every stored quest look changed on re-ingest.
 fn parse_timestamp(s: &str) -> Result<DateTime<Utc>, ApiError> {
     DateTime::parse_from_rfc3339(s)
-        .map(|dt| dt.with_timezone(&Utc))
+        .map(|dt| dt.with_timezone(&Utc).trunc_subsecs(0))
         .map_err(|e| ApiError::InternalError(format!("Failed to parse timestamp: {}", e)))
 }
 
+fn reconstruct_task_v2(task: &QuestTask) -> JsonValue {
+    let mut task_obj = json!({
+        "type": task.task_type,
+        "target": task.target,
+        "applications": task.applications,
+        "external_ids": task.external_ids
+    });
+
+    // Only emitted when received, so quests stored before they were modelled
+    // reconstruct the same
+    for (key, value) in [("assets", &task.assets), ("messages", &task.messages)] {
+        if let Some(value) = value {
+            task_obj[key] = value.clone();
+        }
+    }
+
+    task_obj
+}
+
+/// A task in the legacy `task_config` layout, keyed by `event_name`
+fn reconstruct_task_v1(task: &QuestTask) -> JsonValue {
+    let mut task_obj = json!({
+        "event_name": task.task_type,
+        "target": task.target
+    });
+
+    for (key, value) in [
+        ("applications", &task.applications),
+        ("external_ids", &task.external_ids),
+    ] {
+        if let Some(value) = value {
+            task_obj[key] = value.clone();
+        }
+    }
+
+    task_obj
+}
+
+/// Format a timestamp the way Discord does, e.g. `2025-12-01T16:00:00+00:00`
+fn format_timestamp(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
 /// Reconstruct Discord API format from database (with age filter)
 pub async fn reconstruct_discord_response(
     pool: &MySqlPool,
     age_days: i64,
 ) -> Result<JsonValue, ApiError> {
+    reconstruct_filtered_response(pool, &QuestFilter::default(), age_days).await
+}
+
+/// Reconstruct the quests matching `filter`, in the shape of
+/// [`reconstruct_discord_response`]
+///
+/// `excluded_quests` is not filtered beyond the age window.
+pub async fn reconstruct_filtered_response(
+    pool: &MySqlPool,
+    filter: &QuestFilter,
+    default_age_days: i64,
+) -> Result<JsonValue, ApiError> {
+    let age_days = filter.age_days.unwrap_or(default_age_days);
     let complete_quests =
-        crate::db::quest_operations::get_recent_complete_quests(pool, age_days).await?;
+        crate::db::quest_operations::get_filtered_complete_quests(pool, filter, default_age_days)
+            .await?;
+    let excluded_quests = get_excluded_quests(pool, age_days).await?;
+
+    let replacements: HashMap<&str, Option<&str>> = excluded_quests
+        .iter()
+        .map(|e| (e.id.as_str(), e.replacement_id.as_deref()))
+        .collect();
 
     let mut quests_json = Vec::new();
 
     for cq in complete_quests {
-        let quest_json = reconstruct_single_quest(&cq);
-        quests_json.push(quest_json);
+        let exclusion = replacements.get(cq.quest.id.as_str()).copied();
+        quests_json.push(reconstruct_annotated_quest(&cq, exclusion));
     }
 
+    let excluded_json: Vec<JsonValue> = excluded_quests
+        .iter()
+        .map(|e| {
+            json!({
+                "id": e.id,
+                "replacement_id": e.replacement_id,
+            })
+        })
+        .collect();
+
     Ok(json!({
         "quests": quests_json,
+        "excluded_quests": excluded_json,
     }))
 }
 
+/// Key of the object that holds everything the API adds to a Discord quest
+///
+/// Keeping annotations under one key leaves the rest of the quest exactly in
+/// Discord's shape, so a top-level field Discord adds later cannot collide.
+pub const ANNOTATIONS_KEY: &str = "_annotations";
+
+/// The annotation object of a reconstructed quest, created on first use
+pub fn quest_annotations(
+    quest_json: &mut JsonValue,
+) -> Option<&mut serde_json::Map<String, JsonValue>> {
+    quest_json
+        .as_object_mut()?
+        .entry(ANNOTATIONS_KEY)
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+}
+
+/// Add the decoded names of a quest's reward platforms as `platform_names`
+fn annotate_platforms(quest_json: &mut JsonValue, cq: &CompleteQuest) {
+    let names: Vec<String> = cq
+        .platforms
+        .iter()
+        .map(|p| Platform::from_id(p.platform).to_string())
+        .collect();
+
+    if let Some(annotations) = quest_annotations(quest_json) {
+        annotations.insert("platform_names".to_string(), json!(names));
+    }
+}
+
+/// Explain the tasks of a quest as `tasks`: whether any or all of them are
+/// required, and what each one asks the user to do for how long
+fn annotate_tasks(quest_json: &mut JsonValue, cq: &CompleteQuest) {
+    let items: Vec<TaskSummary> = cq.tasks.iter().map(TaskSummary::from_task).collect();
+    let require = if cq.quest.task_join_operator == "and" {
+        "all"
+    } else {
+        "any"
+    };
+
+    if let Some(annotations) = quest_annotations(quest_json) {
+        annotations.insert(
+            "tasks".to_string(),
+            json!({
+                "require": require,
+                "items": items,
+            }),
+        );
+    }
+}
+
+/// One quest by id, shaped like an entry of [`reconstruct_discord_response`]
+///
+/// Unlike the list, the `QUEST_AGE_DAYS` window does not apply, so links to
+/// long-expired quests keep resolving.
+pub async fn reconstruct_quest_by_id(
+    pool: &MySqlPool,
+    quest_id: &str,
+) -> Result<Option<JsonValue>, ApiError> {
+    let Some(cq) = crate::db::quest_operations::get_complete_quest_by_id(pool, quest_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let excluded = get_excluded_quest(pool, quest_id).await?;
+    let exclusion = excluded.as_ref().map(|e| e.replacement_id.as_deref());
+
+    Ok(Some(reconstruct_annotated_quest(&cq, exclusion)))
+}
+
+/// A reconstructed quest with the API's annotations under [`ANNOTATIONS_KEY`]
+fn reconstruct_annotated_quest(cq: &CompleteQuest, exclusion: Option<Option<&str>>) -> JsonValue {
+    let mut quest_json = reconstruct_single_quest(cq);
+    annotate_platforms(&mut quest_json, cq);
+    annotate_tasks(&mut quest_json, cq);
+    annotate_rewards(&mut quest_json, cq);
+    annotate_exclusion(&mut quest_json, exclusion);
+    quest_json
+}
+
+/// Decode the rewards of a quest into `rewards`, with a category and label each
+fn annotate_rewards(quest_json: &mut JsonValue, cq: &CompleteQuest) {
+    let rewards: Vec<RewardSummary> = cq.rewards.iter().map(RewardSummary::from_reward).collect();
+
+    if let Some(annotations) = quest_annotations(quest_json) {
+        annotations.insert("rewards".to_string(), json!(rewards));
+    }
+}
+
+/// Mark a reconstructed quest as excluded and point at its successor, if any
+///
+/// `exclusion` is `None` for quests that are not excluded, and `Some(replacement_id)`
+/// for excluded ones.
+fn annotate_exclusion(quest_json: &mut JsonValue, exclusion: Option<Option<&str>>) {
+    if let Some(annotations) = quest_annotations(quest_json) {
+        annotations.insert("excluded".to_string(), json!(exclusion.is_some()));
+        annotations.insert("replaced_by".to_string(), json!(exclusion.flatten()));
+    }
+}
+
 /// Reconstruct a single quest in Discord format
-fn reconstruct_single_quest(cq: &CompleteQuest) -> JsonValue {
+///
+/// The result follows the fidelity contract in [`crate::utils::fidelity`].
+pub fn reconstruct_single_quest(cq: &CompleteQuest) -> JsonValue {
     let q = &cq.quest;
     let assets = cq.assets.as_ref();
 
-    // Reconstruct tasks
+    // Reconstruct tasks in the schema they were received in
+    let legacy_tasks = q.task_config_version == Some(1);
     let mut tasks_map = serde_json::Map::new();
     for task in &cq.tasks {
-        let task_obj = json!({
-            "type": task.task_type,
-            "target": task.target,
-            "applications": task.applications,
-            "external_ids": task.external_ids
-        });
+        let task_obj = if legacy_tasks {
+            reconstruct_task_v1(task)
+        } else {
+            reconstruct_task_v2(task)
+        };
         tasks_map.insert(task.task_type.clone(), task_obj);
     }
+    let task_config = json!({
+        "tasks": tasks_map,
+        "join_operator": q.task_join_operator
+    });
 
     // Reconstruct rewards
     let rewards_json: Vec<JsonValue> = cq
@@ -313,18 +1239,19 @@ fn reconstruct_single_quest(cq: &CompleteQuest) -> JsonValue {
         })
         .collect();
 
-    // Reconstruct features
+    // Reconstruct features and platforms
     let features: Vec<i32> = cq.features.iter().map(|f| f.feature_id).collect();
+    let platforms: Vec<i32> = cq.platforms.iter().map(|p| p.platform).collect();
 
     // No user status - we only track quest configuration
 
-    json!({
+    let mut quest_json = json!({
         "id": q.id,
         "config": {
             "id": q.id,
             "config_version": q.config_version,
-            "starts_at": q.starts_at,
-            "expires_at": q.expires_at,
+            "starts_at": format_timestamp(q.starts_at),
+            "expires_at": format_timestamp(q.expires_at),
             "features": features,
             "application": {
                 "id": q.application_id,
@@ -352,15 +1279,11 @@ fn reconstruct_single_quest(cq: &CompleteQuest) -> JsonValue {
                 "game_title": q.game_title,
                 "game_publisher": q.game_publisher
             },
-            "task_config_v2": {
-                "tasks": tasks_map,
-                "join_operator": q.task_join_operator
-            },
             "rewards_config": {
                 "assignment_method": q.reward_assignment_method,
                 "rewards": rewards_json,
-                "rewards_expire_at": q.rewards_expire_at,
-                "platforms": [0]
+                "rewards_expire_at": q.rewards_expire_at.map(format_timestamp),
+                "platforms": platforms
             },
             "share_policy": q.share_policy,
             "cta_config": if q.cta_link.is_some() {
@@ -375,5 +1298,25 @@ fn reconstruct_single_quest(cq: &CompleteQuest) -> JsonValue {
         "user_status": null,
         "targeted_content": [],
         "preview": q.preview
-    })
+    });
+
+    let task_config_key = match q.task_config_version {
+        Some(1) => Some("task_config"),
+        Some(_) => Some("task_config_v2"),
+        None => None,
+    };
+    if let Some(key) = task_config_key {
+        quest_json["config"][key] = task_config;
+    }
+
+    if let Some(extra) = &q.extra {
+        merge_extra(&mut quest_json, extra);
+    }
+
+    if let Some(absent) = &q.absent_fields {
+        let paths: Vec<String> = serde_json::from_value(absent.clone()).unwrap_or_default();
+        remove_paths(&mut quest_json, &paths);
+    }
+
+    quest_json
 }
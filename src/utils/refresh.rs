@@ -0,0 +1,249 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::time::{Duration, Instant};
+
+use sqlx::MySqlPool;
+
+use crate::{
+    config::Config,
+    db::{
+        operations::{
+            delete_cache_variants, prune_cache_variants, upsert_cache, upsert_cache_variant,
+        },
+        payload_operations::mark_payload_ingested,
+        quest_operations::touch_excluded_quests,
+    },
+    utils::{
+        asset_mirror::{annotate_mirrored_assets, MirrorSummary},
+        discord::ensure_success,
+        error::ApiError,
+        payload_archive::archive_upstream_response,
+        quest_filter::QuestFilter,
+        quest_parser::{reconstruct_filtered_response, save_discord_quests_to_db, IngestSummary},
+    },
+    AppState,
+};
+
+/// Cache key of the full reconstructed quest list
+pub const QUEST_CACHE_KEY: &str = "discord_quests";
+
+/// Outcome of the most recent ingest, reported by the health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct LastIngest {
+    pub finished_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub summary: IngestSummary,
+}
+
+/// Fetch quests from the configured source, store them and rebuild the cached response
+///
+/// Concurrent callers share a single refresh: whoever arrives while one is
+/// running waits for it and gets its result.
+pub async fn refresh_quest_cache(state: &AppState) -> Result<JsonValue, ApiError> {
+    state.refresh_flight.run(|| run_refresh(state)).await
+}
+
+/// Refresh in the background after serving a stale response
+///
+/// Attempts are spaced by `REFRESH_MIN_INTERVAL_SECONDS` so a failing upstream
+/// is not retried on every request.
+pub fn spawn_revalidation(state: &AppState) {
+    {
+        let min_interval = Duration::from_secs(state.config.refresh_min_interval_seconds);
+        let mut last = state.last_revalidation.lock().unwrap();
+        if last.is_some_and(|at| at.elapsed() < min_interval) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = refresh_quest_cache(&state).await {
+            tracing::warn!("⚠️  Background revalidation failed: {}", e);
+        }
+    });
+}
+
+/// Rebuild the filtered quest list selected by `filter` and cache it under its own key
+///
+/// Reads the database only; upstream is never called. `source_updated_at` is
+/// when the full list this request is served against was written; a refresh
+/// that lands meanwhile leaves the variant marked as built from the old list.
+/// Beyond `CACHE_MAX_VARIANTS` variants the least recently built are evicted.
+pub async fn refresh_filtered_cache(
+    state: &AppState,
+    filter: &QuestFilter,
+    source_updated_at: DateTime<Utc>,
+) -> Result<JsonValue, ApiError> {
+    let key = filter.cache_key();
+    tracing::debug!("🔍 Building {} from the database", key);
+
+    let list = build_quest_list(&state.db, &state.config, filter).await?;
+
+    // Make room first, so the variant written now is never the one evicted
+    let keep = state.config.cache_max_variants.saturating_sub(1);
+    let evicted = prune_cache_variants(&state.db, QUEST_CACHE_KEY, keep).await?;
+    if evicted > 0 {
+        tracing::debug!(
+            "🧹 Evicted {} filtered variant(s) of {}",
+            evicted,
+            QUEST_CACHE_KEY
+        );
+    }
+
+    upsert_cache_variant(&state.db, &key, &list, source_updated_at).await?;
+
+    Ok(list)
+}
+
+/// Reconstruct the quest list selected by `filter`, with mirrored asset URLs
+/// when `ASSET_REWRITE_URLS` is on
+pub async fn build_quest_list(
+    pool: &MySqlPool,
+    config: &Config,
+    filter: &QuestFilter,
+) -> Result<JsonValue, ApiError> {
+    let mut list = reconstruct_filtered_response(pool, filter, config.quest_age_days).await?;
+
+    if config.rewrites_asset_urls() {
+        annotate_mirrored_assets(pool, &mut list).await?;
+    }
+
+    Ok(list)
+}
+
+async fn run_refresh(state: &AppState) -> Result<JsonValue, ApiError> {
+    // Fetch fresh data from the configured source
+    tracing::info!("📡 Fetching quests from {}", state.source.describe());
+    let response = state.upstream.fetch(state.source.as_ref()).await?;
+
+    // Archive the raw payload before parsing so failures can be inspected later
+    let archive = match archive_upstream_response(
+        &state.db,
+        &response,
+        state.config.payload_archive_retention,
+    )
+    .await
+    {
+        Ok(archive) => Some(archive),
+        Err(e) => {
+            tracing::warn!("⚠️  Failed to archive upstream payload: {}", e);
+            None
+        }
+    };
+
+    ensure_success(&response)?;
+
+    if archive.as_ref().is_some_and(|a| a.unchanged) {
+        tracing::info!("✅ Payload identical to the previous fetch - skipping quest writes");
+
+        // Still reported as excluded, so keep them inside the QUEST_AGE_DAYS window
+        let excluded_ids: Vec<&str> = response
+            .body
+            .get("excluded_quests")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|excluded| excluded.get("id")?.as_str())
+            .collect();
+        touch_excluded_quests(&state.db, &excluded_ids).await?;
+    } else {
+        // Save new and changed quests to database
+        tracing::info!("💾 Checking for new or changed quests...");
+        let summary = save_discord_quests_to_db(&state.db, &response.body).await?;
+
+        if summary.written() > 0 {
+            tracing::info!(
+                "✅ Inserted {} new, updated {} changed, {} unchanged quest(s)",
+                summary.inserted,
+                summary.updated,
+                summary.unchanged
+            );
+        } else {
+            tracing::info!("✅ No new or changed quests - database is up to date");
+        }
+
+        if summary.excluded > 0 {
+            tracing::info!("🚫 Tracking {} excluded quest(s)", summary.excluded);
+        }
+
+        if let Ok(mut last_ingest) = state.last_ingest.write() {
+            *last_ingest = Some(LastIngest {
+                finished_at: Utc::now(),
+                summary,
+            });
+        }
+
+        if let Some(archive) = &archive {
+            mark_payload_ingested(&state.db, &archive.sha256).await?;
+        }
+    }
+
+    let reconstructed = write_quest_cache(state).await?;
+
+    // Mirror newly referenced assets off the refresh path; failures only delay mirroring
+    spawn_asset_mirroring(state);
+
+    Ok(reconstructed)
+}
+
+/// Rebuild the full quest list from the database and cache it, dropping
+/// filtered variants so they are rebuilt on demand
+async fn write_quest_cache(state: &AppState) -> Result<JsonValue, ApiError> {
+    tracing::info!("🔄 Reconstructing response from database");
+    let reconstructed = build_quest_list(&state.db, &state.config, &QuestFilter::default()).await?;
+
+    upsert_cache(&state.db, QUEST_CACHE_KEY, &reconstructed).await?;
+    let dropped = delete_cache_variants(&state.db, QUEST_CACHE_KEY).await?;
+    tracing::info!(
+        "✅ Cache updated for {} ({} filtered variant(s) dropped)",
+        QUEST_CACHE_KEY,
+        dropped
+    );
+
+    Ok(reconstructed)
+}
+
+/// Run [`mirror_assets`] in the background
+pub fn spawn_asset_mirroring(state: &AppState) {
+    if state.asset_mirror.is_none() {
+        return;
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = mirror_assets(&state).await {
+            tracing::warn!("⚠️  Asset mirroring failed: {}", e);
+        }
+    });
+}
+
+/// Mirror the assets of the quests inside `QUEST_AGE_DAYS`
+///
+/// With `ASSET_REWRITE_URLS` on, the cache is rebuilt when new assets arrived
+/// so their URLs are served without waiting for the next refresh.
+pub async fn mirror_assets(state: &AppState) -> Result<MirrorSummary, ApiError> {
+    let Some(mirror) = &state.asset_mirror else {
+        return Ok(MirrorSummary::default());
+    };
+
+    let summary = mirror
+        .mirror_quest_assets(&state.db, state.config.quest_age_days)
+        .await?;
+    if summary.downloaded > 0 || summary.failed > 0 {
+        tracing::info!(
+            "🖼️  Mirrored {} new asset(s), {} failed, {} waiting to retry",
+            summary.downloaded,
+            summary.failed,
+            summary.backed_off
+        );
+    }
+
+    if summary.downloaded > 0 && state.config.rewrites_asset_urls() {
+        write_quest_cache(state).await?;
+    }
+
+    Ok(summary)
+}
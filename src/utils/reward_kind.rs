@@ -0,0 +1,169 @@
+use std::{fmt, str::FromStr};
+
+use serde::Serialize;
+
+use crate::db::quest_models::QuestReward;
+
+/// Reward types this version decodes; anything else is [`RewardKind::Unknown`]
+pub const KNOWN_REWARD_TYPES: [i32; 5] = [1, 2, 3, 4, 5];
+
+/// Discord's reward type (`rewards_config.rewards[].type`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardKind {
+    /// A key or code redeemed outside Discord
+    RewardCode,
+    /// An item delivered inside the game
+    InGame,
+    /// Avatar decorations, profile effects and other Discord collectibles
+    Collectible,
+    /// Orbs, Discord's virtual currency
+    VirtualCurrency,
+    /// A Nitro trial
+    FractionalPremium,
+    /// Any other numeric type; its category is `unknown` but the type is kept
+    Unknown(i32),
+}
+
+impl RewardKind {
+    pub fn from_id(id: i32) -> Self {
+        match id {
+            1 => RewardKind::RewardCode,
+            2 => RewardKind::InGame,
+            3 => RewardKind::Collectible,
+            4 => RewardKind::VirtualCurrency,
+            5 => RewardKind::FractionalPremium,
+            other => RewardKind::Unknown(other),
+        }
+    }
+
+    pub fn id(self) -> i32 {
+        match self {
+            RewardKind::RewardCode => 1,
+            RewardKind::InGame => 2,
+            RewardKind::Collectible => 3,
+            RewardKind::VirtualCurrency => 4,
+            RewardKind::FractionalPremium => 5,
+            RewardKind::Unknown(id) => id,
+        }
+    }
+
+    pub fn category(self) -> RewardCategory {
+        match self {
+            RewardKind::RewardCode => RewardCategory::RewardCode,
+            RewardKind::InGame => RewardCategory::InGame,
+            RewardKind::Collectible => RewardCategory::Collectible,
+            RewardKind::VirtualCurrency => RewardCategory::Orbs,
+            RewardKind::FractionalPremium => RewardCategory::Nitro,
+            RewardKind::Unknown(_) => RewardCategory::Unknown,
+        }
+    }
+
+    /// Short human description of the kind, e.g. "In-game item"
+    pub fn label(self) -> String {
+        match self {
+            RewardKind::RewardCode => "Reward code".to_string(),
+            RewardKind::InGame => "In-game item".to_string(),
+            RewardKind::Collectible => "Collectible".to_string(),
+            RewardKind::VirtualCurrency => "Orbs".to_string(),
+            RewardKind::FractionalPremium => "Nitro trial".to_string(),
+            RewardKind::Unknown(id) => format!("Unknown reward type {}", id),
+        }
+    }
+}
+
+/// What a reward is, independent of how Discord numbers it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardCategory {
+    RewardCode,
+    InGame,
+    Collectible,
+    Orbs,
+    Nitro,
+    Unknown,
+}
+
+impl RewardCategory {
+    /// Discord's reward type of this category, `None` for `Unknown`
+    pub fn reward_type(self) -> Option<i32> {
+        let kind = match self {
+            RewardCategory::RewardCode => RewardKind::RewardCode,
+            RewardCategory::InGame => RewardKind::InGame,
+            RewardCategory::Collectible => RewardKind::Collectible,
+            RewardCategory::Orbs => RewardKind::VirtualCurrency,
+            RewardCategory::Nitro => RewardKind::FractionalPremium,
+            RewardCategory::Unknown => return None,
+        };
+
+        Some(kind.id())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RewardCategory::RewardCode => "reward_code",
+            RewardCategory::InGame => "in_game",
+            RewardCategory::Collectible => "collectible",
+            RewardCategory::Orbs => "orbs",
+            RewardCategory::Nitro => "nitro",
+            RewardCategory::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for RewardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts a category name in any case, or a numeric Discord reward type
+impl FromStr for RewardCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(id) = s.parse::<i32>() {
+            return Ok(RewardKind::from_id(id).category());
+        }
+
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "reward_code" | "code" => Ok(RewardCategory::RewardCode),
+            "in_game" => Ok(RewardCategory::InGame),
+            "collectible" => Ok(RewardCategory::Collectible),
+            "orbs" => Ok(RewardCategory::Orbs),
+            "nitro" => Ok(RewardCategory::Nitro),
+            "unknown" => Ok(RewardCategory::Unknown),
+            _ => Err(format!(
+                "Unknown reward '{}' (expected orbs, collectible, in_game, reward_code, nitro, unknown or a numeric type)",
+                s
+            )),
+        }
+    }
+}
+
+/// Entry of the `rewards` annotation, decoded from `rewards_config.rewards[]`
+#[derive(Debug, Clone, Serialize)]
+pub struct RewardSummary {
+    #[serde(rename = "type")]
+    pub reward_type: i32,
+    pub category: RewardCategory,
+    pub label: String,
+    /// The reward's own name, e.g. "700 Orbs"
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub orb_quantity: Option<i32>,
+}
+
+impl RewardSummary {
+    pub fn from_reward(reward: &QuestReward) -> Self {
+        let kind = RewardKind::from_id(reward.reward_type);
+
+        Self {
+            reward_type: reward.reward_type,
+            category: kind.category(),
+            label: kind.label(),
+            name: reward.reward_name.clone(),
+            orb_quantity: reward.orb_quantity,
+        }
+    }
+}
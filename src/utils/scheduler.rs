@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use rand::Rng;
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::refresh::refresh_quest_cache;
+use crate::AppState;
+
+/// Background task that keeps the quest cache fresh
+pub struct RefreshScheduler {
+    stop: watch::Sender<bool>,
+    handle: JoinHandle<()>,
+}
+
+impl RefreshScheduler {
+    /// Start refreshing every `refresh_interval`, first waiting one interval
+    /// (or the minimum interval if `initial_refresh_ok` is false)
+    pub fn spawn(state: AppState, initial_refresh_ok: bool) -> Self {
+        let (stop, mut stopped) = watch::channel(false);
+
+        let handle = tokio::spawn(async move {
+            let mut last_ok = initial_refresh_ok;
+
+            loop {
+                let delay = next_delay(&state, last_ok);
+                tracing::debug!("⏲️  Next quest refresh in {:?}", delay);
+
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = stopped.changed() => break,
+                }
+
+                // An in-flight refresh is allowed to finish before stopping
+                last_ok = match refresh_quest_cache(&state).await {
+                    Ok(_) => true,
+                    Err(e) => {
+                        tracing::warn!("⚠️  Background quest refresh failed: {}", e);
+                        false
+                    }
+                };
+            }
+
+            tracing::info!("🛑 Background refresh stopped");
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stop the task, waiting for an in-flight refresh to finish
+    pub async fn stop(self) {
+        let _ = self.stop.send(true);
+        if let Err(e) = self.handle.await {
+            tracing::warn!("⚠️  Background refresh task ended abnormally: {}", e);
+        }
+    }
+}
+
+/// Refresh interval with ± jitter; failed refreshes are retried after the
+/// minimum interval instead of a full cache period
+fn next_delay(state: &AppState, last_ok: bool) -> Duration {
+    let config = &state.config;
+    let base = if last_ok {
+        config.refresh_interval()
+    } else {
+        Duration::from_secs(config.refresh_min_interval_seconds)
+    }
+    .max(Duration::from_secs(1));
+
+    let jitter = base.as_secs_f64() * config.refresh_jitter_percent as f64 / 100.0;
+    if jitter <= 0.0 {
+        return base;
+    }
+
+    let offset = rand::thread_rng().gen_range(-jitter..=jitter);
+    Duration::from_secs_f64((base.as_secs_f64() + offset).max(1.0))
+}
@@ -0,0 +1,54 @@
+use std::future::Future;
+
+use tokio::sync::Mutex;
+
+/// Runs at most one call at a time and hands its result to everyone who was
+/// waiting for it
+///
+/// A caller that arrives while a call is in flight waits for it and receives a
+/// clone of its result instead of starting another one. If the in-flight call
+/// is cancelled, the next waiter runs its own.
+pub struct SingleFlight<T> {
+    running: Mutex<()>,
+    /// Number of completed calls and the result of the latest one
+    completed: std::sync::Mutex<(u64, Option<T>)>,
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(()),
+            completed: std::sync::Mutex::new((0, None)),
+        }
+    }
+
+    pub async fn run<F, Fut>(&self, call: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let seen = self.completed.lock().unwrap().0;
+        let _running = self.running.lock().await;
+
+        // A call finished while we waited for the lock: share its result
+        if let (generation, Some(result)) = &*self.completed.lock().unwrap() {
+            if *generation != seen {
+                return result.clone();
+            }
+        }
+
+        let result = call().await;
+
+        let mut completed = self.completed.lock().unwrap();
+        completed.0 += 1;
+        completed.1 = Some(result.clone());
+
+        result
+    }
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
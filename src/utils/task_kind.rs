@@ -0,0 +1,169 @@
+use std::{fmt, time::Duration};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::db::quest_models::QuestTask;
+
+/// What a quest task asks the user to do (the `task_config_v2.tasks` key)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskKind {
+    PlayOnDesktop,
+    StreamOnDesktop,
+    WatchVideo,
+    WatchVideoOnMobile,
+    PlayActivity,
+    /// Any other task key, served as-is with `known: false`
+    Unknown(String),
+}
+
+impl TaskKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TaskKind::PlayOnDesktop => "PLAY_ON_DESKTOP",
+            TaskKind::StreamOnDesktop => "STREAM_ON_DESKTOP",
+            TaskKind::WatchVideo => "WATCH_VIDEO",
+            TaskKind::WatchVideoOnMobile => "WATCH_VIDEO_ON_MOBILE",
+            TaskKind::PlayActivity => "PLAY_ACTIVITY",
+            TaskKind::Unknown(raw) => raw,
+        }
+    }
+
+    /// How the target is measured; every known kind counts seconds
+    pub fn target_duration(&self, target: i32) -> Option<Duration> {
+        match self {
+            TaskKind::Unknown(_) => None,
+            _ => Some(Duration::from_secs(target.max(0) as u64)),
+        }
+    }
+
+    /// What the user has to do, without the duration
+    pub fn action(&self) -> String {
+        match self {
+            TaskKind::PlayOnDesktop => {
+                "Play the game with the Discord desktop app open".to_string()
+            }
+            TaskKind::StreamOnDesktop => {
+                "Stream the game to a friend in a voice channel from the desktop app".to_string()
+            }
+            TaskKind::WatchVideo => "Watch the quest video".to_string(),
+            TaskKind::WatchVideoOnMobile => "Watch the quest video in the mobile app".to_string(),
+            TaskKind::PlayActivity => "Play the activity in a voice channel".to_string(),
+            TaskKind::Unknown(raw) => format!("Complete the {} task", raw),
+        }
+    }
+
+    pub fn is_video(&self) -> bool {
+        matches!(self, TaskKind::WatchVideo | TaskKind::WatchVideoOnMobile)
+    }
+}
+
+impl From<&str> for TaskKind {
+    fn from(raw: &str) -> Self {
+        match raw {
+            "PLAY_ON_DESKTOP" => TaskKind::PlayOnDesktop,
+            "STREAM_ON_DESKTOP" => TaskKind::StreamOnDesktop,
+            "WATCH_VIDEO" => TaskKind::WatchVideo,
+            "WATCH_VIDEO_ON_MOBILE" => TaskKind::WatchVideoOnMobile,
+            "PLAY_ACTIVITY" => TaskKind::PlayActivity,
+            other => TaskKind::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The video a WATCH_VIDEO task plays, from the task's `assets` and `messages`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskVideo {
+    pub url: Option<String>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub thumbnail: Option<String>,
+    pub title: Option<String>,
+}
+
+impl TaskVideo {
+    pub fn from_task(task: &QuestTask) -> Option<Self> {
+        let video = task.assets.as_ref()?.get("video")?;
+
+        Some(Self {
+            url: string_at(video, "url"),
+            width: video.get("width").and_then(JsonValue::as_i64),
+            height: video.get("height").and_then(JsonValue::as_i64),
+            thumbnail: string_at(video, "thumbnail"),
+            title: task
+                .messages
+                .as_ref()
+                .and_then(|messages| string_at(messages, "video_title")),
+        })
+    }
+}
+
+fn string_at(value: &JsonValue, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(JsonValue::as_str)
+        .map(str::to_string)
+}
+
+/// Item of the `tasks` annotation: the target of one task as a duration, with a
+/// sentence saying what to do
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSummary {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Whether we know this kind; unknown kinds have no duration
+    pub known: bool,
+    pub target: i32,
+    pub duration_seconds: Option<u64>,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<TaskVideo>,
+}
+
+impl TaskSummary {
+    pub fn from_task(task: &QuestTask) -> Self {
+        let kind = TaskKind::from(task.task_type.as_str());
+        let duration = kind.target_duration(task.target);
+        let description = match duration {
+            Some(duration) => format!("{} for {}", kind.action(), format_duration(duration)),
+            None => kind.action(),
+        };
+
+        Self {
+            kind: kind.to_string(),
+            known: !matches!(kind, TaskKind::Unknown(_)),
+            target: task.target,
+            duration_seconds: duration.map(|d| d.as_secs()),
+            description,
+            video: TaskVideo::from_task(task),
+        }
+    }
+}
+
+/// `90` seconds as "1 minute 30 seconds", `3600` as "1 hour"
+pub fn format_duration(duration: Duration) -> String {
+    let total = duration.as_secs();
+    let parts = [
+        (total / 3600, "hour"),
+        (total % 3600 / 60, "minute"),
+        (total % 60, "second"),
+    ];
+
+    let words: Vec<String> = parts
+        .iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, unit)| format!("{} {}{}", amount, unit, if *amount == 1 { "" } else { "s" }))
+        .collect();
+
+    if words.is_empty() {
+        "0 seconds".to_string()
+    } else {
+        words.join(" ")
+    }
+}
@@ -0,0 +1,250 @@
+use std::{sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{discord::UpstreamResponse, error::ApiError};
+use crate::{config::Config, sources::QuestSource};
+
+/// Used when a 429 carries no usable `Retry-After`
+const DEFAULT_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(60);
+
+/// Retry and circuit breaker settings for upstream fetches
+#[derive(Debug, Clone)]
+pub struct UpstreamPolicy {
+    /// Extra attempts after a network error or 5xx within one fetch
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each following one
+    pub retry_backoff: Duration,
+    /// Consecutive failed fetches that open the circuit
+    pub breaker_threshold: u32,
+    /// How long an open circuit rejects fetches before allowing a trial
+    pub breaker_cooldown: Duration,
+    /// Longest pause a rate limit may impose, whatever upstream asks for
+    pub max_rate_limit_pause: Duration,
+}
+
+impl UpstreamPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_retries: config.upstream_max_retries,
+            retry_backoff: Duration::from_millis(config.upstream_retry_backoff_ms),
+            breaker_threshold: config.upstream_breaker_threshold.max(1),
+            breaker_cooldown: Duration::from_secs(config.upstream_breaker_cooldown_seconds),
+            max_rate_limit_pause: Duration::from_secs(config.upstream_max_rate_limit_pause_seconds),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    /// The cooldown has passed; the next fetch decides whether to close again
+    HalfOpen,
+}
+
+/// Whether upstream accepts our credential
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthState {
+    /// No authenticated fetch has completed yet, or the source has no credential
+    #[default]
+    Unknown,
+    Valid,
+    /// Rejected; upstream is not called again until the credential changes
+    Invalid,
+}
+
+/// Snapshot of the guard, reported by the health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamStatus {
+    pub auth: AuthState,
+    pub circuit: CircuitState,
+    pub consecutive_failures: u32,
+    pub circuit_open_until: Option<DateTime<Utc>>,
+    pub rate_limited_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    auth: AuthState,
+    consecutive_failures: u32,
+    open_until: Option<DateTime<Utc>>,
+    paused_until: Option<DateTime<Utc>>,
+}
+
+/// Protects upstream from us: retries transient failures with backoff,
+/// honours rate limits for every caller and stops calling a failing upstream
+pub struct UpstreamGuard {
+    policy: UpstreamPolicy,
+    state: Mutex<GuardState>,
+}
+
+impl UpstreamGuard {
+    pub fn new(policy: UpstreamPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(GuardState::default()),
+        }
+    }
+
+    /// Fetch from `source`, unless a rate limit or open circuit forbids it
+    ///
+    /// Network errors and 5xx responses are retried up to `max_retries` times.
+    /// As with [`QuestSource::fetch`], non-2xx responses are returned so they
+    /// can be archived.
+    pub async fn fetch(&self, source: &dyn QuestSource) -> Result<UpstreamResponse, ApiError> {
+        self.check_credentials(source).await?;
+        self.check_allowed()?;
+
+        let mut attempt = 0;
+        let result = loop {
+            let result = source.fetch().await;
+            let retryable = match &result {
+                Ok(response) => response.status >= 500,
+                Err(_) => true,
+            };
+
+            if !retryable || attempt >= self.policy.max_retries {
+                break result;
+            }
+
+            let delay = self.policy.retry_backoff * 2u32.saturating_pow(attempt);
+            attempt += 1;
+            tracing::warn!(
+                "🔁 Upstream attempt {} failed ({}), retrying in {:?}",
+                attempt,
+                describe_failure(&result),
+                delay
+            );
+            tokio::time::sleep(delay).await;
+        };
+
+        self.record(source, &result);
+        result
+    }
+
+    pub fn status(&self) -> UpstreamStatus {
+        let state = self.state.lock().unwrap();
+        let now = Utc::now();
+
+        let circuit = match state.open_until {
+            Some(until) if until > now => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+            None => CircuitState::Closed,
+        };
+
+        UpstreamStatus {
+            auth: state.auth,
+            circuit,
+            consecutive_failures: state.consecutive_failures,
+            circuit_open_until: state.open_until.filter(|until| *until > now),
+            rate_limited_until: state.paused_until.filter(|until| *until > now),
+        }
+    }
+
+    /// After a rejected credential, only call upstream once it has been replaced
+    async fn check_credentials(&self, source: &dyn QuestSource) -> Result<(), ApiError> {
+        if self.state.lock().unwrap().auth != AuthState::Invalid {
+            return Ok(());
+        }
+
+        // Read outside the lock; it is only taken again to record the outcome
+        if source.reload_credentials().await {
+            tracing::info!("🔑 Replaced credential detected, resuming upstream fetches");
+            self.state.lock().unwrap().auth = AuthState::Unknown;
+            return Ok(());
+        }
+
+        Err(ApiError::UpstreamAuthError(
+            "Upstream rejected the configured credential; waiting for it to be replaced"
+                .to_string(),
+        ))
+    }
+
+    fn check_allowed(&self) -> Result<(), ApiError> {
+        let state = self.state.lock().unwrap();
+        let now = Utc::now();
+
+        if let Some(until) = state.paused_until.filter(|until| *until > now) {
+            return Err(ApiError::DiscordApiError(format!(
+                "Rate limited by upstream until {}",
+                until.to_rfc3339()
+            )));
+        }
+
+        if let Some(until) = state.open_until.filter(|until| *until > now) {
+            return Err(ApiError::DiscordApiError(format!(
+                "Circuit open after {} consecutive upstream failures, retrying after {}",
+                state.consecutive_failures,
+                until.to_rfc3339()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn record(&self, source: &dyn QuestSource, result: &Result<UpstreamResponse, ApiError>) {
+        let mut state = self.state.lock().unwrap();
+
+        if source.has_credentials() {
+            match result {
+                Ok(response) if response.is_auth_failure() => {
+                    tracing::error!(
+                        "🔑 Upstream rejected the credential; upstream fetches stop until it is replaced"
+                    );
+                    state.auth = AuthState::Invalid;
+                }
+                Ok(response) if response.is_success() => state.auth = AuthState::Valid,
+                _ => {}
+            }
+        }
+
+        match result {
+            Ok(response) if response.status == 429 => {
+                let pause = response
+                    .retry_after
+                    .unwrap_or(DEFAULT_RATE_LIMIT_PAUSE)
+                    .min(self.policy.max_rate_limit_pause);
+                let until = Utc::now()
+                    + chrono::Duration::from_std(pause).unwrap_or(chrono::Duration::zero());
+                tracing::warn!(
+                    "🐢 Rate limited by upstream, pausing refreshes until {}",
+                    until.to_rfc3339()
+                );
+                state.paused_until = Some(until);
+            }
+            Ok(response) if response.status < 500 => {
+                if state.open_until.is_some() {
+                    tracing::info!("✅ Upstream recovered, closing circuit");
+                }
+                state.consecutive_failures = 0;
+                state.open_until = None;
+            }
+            _ => {
+                state.consecutive_failures += 1;
+
+                if state.consecutive_failures >= self.policy.breaker_threshold {
+                    let until = Utc::now()
+                        + chrono::Duration::from_std(self.policy.breaker_cooldown)
+                            .unwrap_or(chrono::Duration::zero());
+                    tracing::error!(
+                        "⛔ {} consecutive upstream failures, opening circuit until {}",
+                        state.consecutive_failures,
+                        until.to_rfc3339()
+                    );
+                    state.open_until = Some(until);
+                }
+            }
+        }
+    }
+}
+
+fn describe_failure(result: &Result<UpstreamResponse, ApiError>) -> String {
+    match result {
+        Ok(response) => format!("HTTP {}", response.status),
+        Err(e) => e.to_string(),
+    }
+}
@@ -0,0 +1,53 @@
+//! Admin authentication. Rejected requests never reach the database, so a
+//! lazy pool that is never connected is enough.
+
+mod common;
+
+use kythia_quest_api::utils::secret::Secret;
+use sqlx::MySqlPool;
+
+use common::{serve, test_config, test_state};
+
+async fn start(admin_token: Option<&str>) -> String {
+    let pool = MySqlPool::connect_lazy("mysql://unused@127.0.0.1:1/unused").unwrap();
+    let mut config = test_config("http://127.0.0.1:1/quests".to_string());
+    config.admin_token = admin_token.map(Secret::from);
+
+    serve(test_state(pool, config)).await
+}
+
+async fn status(app: &str, authorization: Option<&str>) -> u16 {
+    let mut request = reqwest::Client::new().get(format!("{}/v1/admin/payloads", app));
+    if let Some(authorization) = authorization {
+        request = request.header("Authorization", authorization);
+    }
+
+    request.send().await.unwrap().status().as_u16()
+}
+
+#[test]
+fn secrets_match_only_the_exact_value() {
+    let secret = Secret::from("admin-bearer");
+
+    assert!(secret.matches("admin-bearer"));
+    assert!(!secret.matches("admin-bearer "));
+    assert!(!secret.matches("admin"));
+    assert!(!secret.matches(""));
+}
+
+#[tokio::test]
+async fn admin_routes_require_the_token() {
+    let app = start(Some("admin-bearer")).await;
+
+    assert_eq!(status(&app, None).await, 401);
+    assert_eq!(status(&app, Some("Bearer wrong")).await, 401);
+    assert_eq!(status(&app, Some("admin-bearer")).await, 401);
+}
+
+#[tokio::test]
+async fn admin_routes_are_disabled_without_a_token() {
+    let app = start(None).await;
+
+    assert_eq!(status(&app, None).await, 404);
+    assert_eq!(status(&app, Some("Bearer anything")).await, 404);
+}
@@ -0,0 +1,131 @@
+//! Absolute asset URLs of the resolved response format. No database needed.
+
+use kythia_quest_api::{
+    mock_discord::default_payload,
+    utils::asset_url::{
+        resolve_asset_url, resolve_quest_assets, ImageFormat, ImageHints, ResponseFormat,
+    },
+};
+use serde_json::json;
+
+const CDN: &str = "https://cdn.discordapp.com";
+
+#[test]
+fn keys_resolve_under_the_base_url() {
+    let hints = ImageHints::default();
+
+    assert_eq!(
+        resolve_asset_url(CDN, "quests/1/hero.png", &hints),
+        "https://cdn.discordapp.com/quests/1/hero.png"
+    );
+    assert_eq!(
+        resolve_asset_url("https://assets.example.com/", "/quests/1/hero.png", &hints),
+        "https://assets.example.com/quests/1/hero.png"
+    );
+    assert_eq!(
+        resolve_asset_url(CDN, "https://media.example.com/hero.png", &hints),
+        "https://media.example.com/hero.png"
+    );
+}
+
+#[test]
+fn hints_apply_to_images_only() {
+    let hints = ImageHints {
+        size: Some(512),
+        format: Some(ImageFormat::Webp),
+    };
+
+    assert_eq!(
+        resolve_asset_url(CDN, "quests/1/hero.png", &hints),
+        "https://cdn.discordapp.com/quests/1/hero.png?format=webp&size=512"
+    );
+    assert_eq!(
+        resolve_asset_url(CDN, "quests/1/hero.webm", &hints),
+        "https://cdn.discordapp.com/quests/1/hero.webm"
+    );
+}
+
+#[test]
+fn hints_and_formats_are_validated() {
+    assert_eq!(ImageHints::parse_size("1024"), Ok(1024));
+    assert!(ImageHints::parse_size("1000").is_err());
+    assert!(ImageHints::parse_size("8192").is_err());
+    assert_eq!("JPEG".parse(), Ok(ImageFormat::Jpg));
+    assert!("bmp".parse::<ImageFormat>().is_err());
+    assert_eq!("resolved".parse(), Ok(ResponseFormat::Resolved));
+    assert!("compact".parse::<ResponseFormat>().is_err());
+}
+
+#[test]
+fn every_asset_field_of_a_quest_is_resolved() {
+    let mut quest = default_payload()["quests"][0].clone();
+    quest["_annotations"] = json!({
+        "tasks": {
+            "require": "any",
+            "items": [{ "video": { "url": "quests/1/video.mp4", "thumbnail": "quests/1/thumb.png" } }]
+        }
+    });
+    let hints = ImageHints {
+        size: Some(256),
+        format: None,
+    };
+
+    resolve_quest_assets(&mut quest, CDN, &hints);
+
+    let assets = &quest["config"]["assets"];
+    assert_eq!(
+        assets["game_tile_dark"],
+        "https://cdn.discordapp.com/quests/1412491570820812933/game_tile_dark.png?size=256"
+    );
+    assert_eq!(
+        assets["logotype_light"],
+        "https://cdn.discordapp.com/quests/1412491570820812933/logotype_light.png?size=256"
+    );
+    assert_eq!(assets["hero_video"], serde_json::Value::Null);
+
+    let video = &quest["config"]["task_config_v2"]["tasks"]["WATCH_VIDEO"]["assets"]["video"];
+    assert_eq!(
+        video["url"],
+        "https://cdn.discordapp.com/quests/1412491570820812933/video.mp4"
+    );
+    assert_eq!(
+        video["thumbnail"],
+        "https://cdn.discordapp.com/quests/1412491570820812933/video_thumbnail.png?size=256"
+    );
+
+    assert_eq!(
+        quest["_annotations"]["tasks"]["items"][0]["video"]["url"],
+        "https://cdn.discordapp.com/quests/1/video.mp4"
+    );
+}
+
+#[test]
+fn missing_sections_are_not_invented() {
+    let mut quest = json!({ "id": "1", "config": { "task_config": { "tasks": {} } } });
+    let before = quest.clone();
+
+    resolve_quest_assets(&mut quest, CDN, &ImageHints::default());
+
+    assert_eq!(quest, before);
+}
+
+#[test]
+fn mirrored_assets_win_over_the_cdn() {
+    let mut quest = default_payload()["quests"][0].clone();
+    quest["_annotations"] = json!({
+        "mirrored_assets": { "game_tile_dark": "/v1/assets/abc" }
+    });
+    let hints = ImageHints {
+        size: Some(256),
+        format: None,
+    };
+
+    resolve_quest_assets(&mut quest, CDN, &hints);
+
+    let assets = &quest["config"]["assets"];
+    assert_eq!(assets["game_tile_dark"], "/v1/assets/abc");
+    assert_eq!(
+        assets["logotype_light"],
+        "https://cdn.discordapp.com/quests/1412491570820812933/logotype_light.png?size=256"
+    );
+}
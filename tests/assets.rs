@@ -0,0 +1,290 @@
+//! Asset mirroring against the mock server's stand-in CDN. The store tests
+//! need no database; the endpoint tests are skipped unless `TEST_DATABASE_URL`
+//! points at a scratch MySQL database.
+
+mod common;
+
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use chrono::Utc;
+use kythia_quest_api::{
+    db::asset_operations::get_quest_asset_paths,
+    mock_discord::{default_payload, mock_asset_body, MockDiscord},
+    utils::{
+        asset_mirror::{is_content_hash, media_type, AssetMirror, RetryBackoff},
+        refresh::{mirror_assets, refresh_quest_cache, QUEST_CACHE_KEY},
+    },
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+
+use common::{serve, test_config, test_pool, test_state, DB_LOCK};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+async fn start_cdn() -> MockDiscord {
+    MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap()
+}
+
+fn mirror(cdn: &MockDiscord, dir: &PathBuf, max_bytes: u64) -> AssetMirror {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(2))
+        .build()
+        .unwrap();
+    AssetMirror::new(
+        client,
+        &cdn.cdn_url(),
+        dir,
+        max_bytes,
+        Duration::from_secs(60),
+    )
+}
+
+#[tokio::test]
+async fn downloads_are_stored_by_content_hash() {
+    let cdn = start_cdn().await;
+    let dir = scratch_dir("asset-mirror-store");
+    let mirror = mirror(&cdn, &dir, 1024);
+
+    let path = "quests/1412491570820812933/hero.png";
+    let asset = mirror.download(path).await.unwrap();
+
+    let expected = format!("{:x}", Sha256::digest(mock_asset_body(path)));
+    assert_eq!(asset.sha256, expected);
+    assert!(is_content_hash(&asset.sha256));
+    assert_eq!(asset.source_path, path);
+    assert_eq!(asset.content_type, "image/png");
+    assert_eq!(asset.size_bytes, mock_asset_body(path).len() as i64);
+    assert_eq!(
+        mirror.read(&asset.sha256).await.unwrap(),
+        mock_asset_body(path)
+    );
+    assert!(mirror
+        .file_path(&asset.sha256)
+        .starts_with(dir.join(&expected[..2])));
+
+    // Another quest's identical hero shares the stored file
+    let other = mirror
+        .download("quests/1419012345678901234/hero.png")
+        .await
+        .unwrap();
+    assert_eq!(other.sha256, asset.sha256);
+    assert_eq!(cdn.asset_request_count(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn oversized_and_missing_assets_are_rejected() {
+    let cdn = start_cdn().await;
+    let dir = scratch_dir("asset-mirror-rejects");
+
+    let small = mirror(&cdn, &dir, 4);
+    assert!(small
+        .download("quests/1412491570820812933/hero.png")
+        .await
+        .is_err());
+
+    let mirror = mirror(&cdn, &dir, 1024);
+    assert!(mirror.download("not-a-quest/hero.png").await.is_err());
+
+    // Only images and videos are mirrored
+    assert!(mirror
+        .download("quests/1412491570820812933/page.html")
+        .await
+        .is_err());
+
+    // Nothing was stored for either failure
+    assert!(!dir.exists() || std::fs::read_dir(&dir).unwrap().next().is_none());
+}
+
+#[test]
+fn failed_assets_wait_longer_after_each_failure() {
+    let backoff = RetryBackoff::new(Duration::from_secs(60));
+    let now = Utc::now();
+    let path = "quests/1/hero.png";
+    assert!(!backoff.is_waiting(path, now));
+
+    let retry_at = backoff.record_failure(path, now);
+    assert_eq!(retry_at, now + chrono::Duration::seconds(60));
+    assert!(backoff.is_waiting(path, now + chrono::Duration::seconds(59)));
+    assert!(!backoff.is_waiting(path, retry_at));
+    assert!(!backoff.is_waiting("quests/2/hero.png", now));
+
+    assert_eq!(
+        backoff.record_failure(path, now),
+        now + chrono::Duration::seconds(120)
+    );
+
+    // The wait is capped at a day however often a path fails
+    for _ in 0..40 {
+        backoff.record_failure(path, now);
+    }
+    assert_eq!(
+        backoff.record_failure(path, now),
+        now + chrono::Duration::days(1)
+    );
+
+    backoff.record_success(path);
+    assert!(!backoff.is_waiting(path, now));
+}
+
+#[test]
+fn only_image_and_video_types_are_allowed() {
+    assert_eq!(media_type("image/png"), Some("image/png"));
+    assert_eq!(media_type("Video/WebM; codecs=vp9"), Some("video/webm"));
+    assert_eq!(media_type("image/svg+xml"), None);
+    assert_eq!(media_type("text/html; charset=utf-8"), None);
+    assert_eq!(media_type("application/octet-stream"), None);
+}
+
+#[test]
+fn content_hashes_are_lowercase_sha256() {
+    let hash = format!("{:x}", Sha256::digest(b"asset"));
+    assert!(is_content_hash(&hash));
+    assert!(!is_content_hash(&hash.to_uppercase()));
+    assert!(!is_content_hash(&hash[..63]));
+    assert!(!is_content_hash("../../etc/passwd"));
+}
+
+/// Remove the fixture quests, their mirrored assets and the cached response
+async fn reset(pool: &MySqlPool) {
+    for quest in default_payload()["quests"].as_array().unwrap() {
+        let id = quest["id"].as_str().unwrap();
+        sqlx::query("DELETE FROM quests WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM mirrored_assets WHERE source_path LIKE ?")
+            .bind(format!("quests/{}/%", id))
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    sqlx::query("DELETE FROM cache_store WHERE id = ?")
+        .bind(QUEST_CACHE_KEY)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn mirrored_assets_are_served_and_linked() {
+    let _guard = DB_LOCK.lock().await;
+    let Some(pool) = test_pool().await else {
+        return;
+    };
+    reset(&pool).await;
+
+    let mock = start_cdn().await;
+    let dir = scratch_dir("asset-mirror-endpoint");
+    let mut config = test_config(mock.quests_url());
+    config.asset_mirror_dir = Some(dir.clone());
+    config.asset_cdn_url = mock.cdn_url();
+    config.asset_rewrite_urls = true;
+    let state = test_state(pool.clone(), config);
+    let app = serve(state.clone()).await;
+
+    // Mirroring runs after the refresh has answered; wait for the cache it rebuilds
+    let mut quest = Value::Null;
+    for _ in 0..50 {
+        let body: Value = reqwest::get(format!("{}/v1/quests", app))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        quest = body["quests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|quest| quest["id"] == "1419012345678901234")
+            .unwrap()
+            .clone();
+        if quest["_annotations"]["mirrored_assets"]
+            .get("hero_video")
+            .is_some()
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // The Discord-shaped assets are untouched; mirrored URLs sit beside them
+    assert_eq!(
+        quest["config"]["assets"]["hero_video"],
+        "quests/1419012345678901234/hero.webm"
+    );
+    let url = quest["_annotations"]["mirrored_assets"]["hero_video"]
+        .as_str()
+        .unwrap();
+    assert!(quest["_annotations"]["mirrored_assets"]
+        .get("game_tile_light")
+        .is_none());
+
+    let response = reqwest::get(format!("{}{}", app, url)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "video/webm");
+    assert_eq!(response.headers()["x-content-type-options"], "nosniff");
+    assert!(response.headers()["cache-control"]
+        .to_str()
+        .unwrap()
+        .contains("immutable"));
+    assert_eq!(
+        response.bytes().await.unwrap().to_vec(),
+        mock_asset_body("hero.webm")
+    );
+
+    // Already mirrored assets are not downloaded again
+    let downloads = mock.asset_request_count();
+    refresh_quest_cache(&state).await.unwrap();
+    let summary = mirror_assets(&state).await.unwrap();
+    assert_eq!(summary.downloaded, 0);
+    assert_eq!(mock.asset_request_count(), downloads);
+
+    // The resolved format links the mirrored copy, in the list and for one quest
+    for path in [
+        "/v1/quests?format=resolved",
+        "/v1/quests/1419012345678901234?format=resolved",
+    ] {
+        let body: Value = reqwest::get(format!("{}{}", app, path))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let quest = body["quests"]
+            .as_array()
+            .and_then(|quests| quests.iter().find(|q| q["id"] == "1419012345678901234"))
+            .unwrap_or(&body);
+        assert_eq!(quest["config"]["assets"]["hero_video"], url, "{}", path);
+    }
+
+    // Quests outside the age window are not mirrored at all
+    sqlx::query("UPDATE quests SET expires_at = '2000-01-01 00:00:00' WHERE id = ?")
+        .bind("1419012345678901234")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let paths = get_quest_asset_paths(&pool, 30).await.unwrap();
+    assert!(!paths
+        .iter()
+        .any(|path| path.starts_with("quests/1419012345678901234/")));
+
+    let missing = reqwest::get(format!("{}/v1/assets/{}", app, "0".repeat(64)))
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 404);
+
+    reset(&pool).await;
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,89 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+
+use kythia_quest_api::{
+    build_router,
+    config::{Config, QuestSourceConfig, RefreshMode},
+    sources,
+    utils::secret::Secret,
+    AppState,
+};
+use sqlx::MySqlPool;
+use tokio::sync::Mutex;
+
+/// Serializes tests that share the scratch database
+pub static DB_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Scratch database for tests that need MySQL, or `None` to skip them
+pub async fn test_pool() -> Option<MySqlPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("skipping: TEST_DATABASE_URL is not set");
+        return None;
+    };
+
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    Some(pool)
+}
+
+pub fn test_config(discord_api_url: String) -> Config {
+    Config {
+        quest_source: QuestSourceConfig::Discord,
+        discord_token: Some(Secret::from("mock-token")),
+        discord_token_file: None,
+        discord_token_in_environment: true,
+        discord_api_url,
+        upstream_connect_timeout_seconds: 2,
+        upstream_timeout_seconds: 2,
+        upstream_max_retries: 0,
+        upstream_retry_backoff_ms: 10,
+        upstream_breaker_threshold: 100,
+        upstream_breaker_cooldown_seconds: 1,
+        upstream_max_rate_limit_pause_seconds: 3600,
+        database_url: Secret::from(std::env::var("TEST_DATABASE_URL").unwrap_or_default()),
+        port: 0,
+        cache_duration_minutes: 30,
+        cache_max_variants: 100,
+        max_staleness_minutes: 60,
+        refresh_mode: RefreshMode::OnDemand,
+        refresh_min_interval_seconds: 60,
+        refresh_jitter_percent: 10,
+        quest_age_days: 30,
+        payload_archive_retention: 100,
+        asset_mirror_dir: None,
+        asset_cdn_url: String::new(),
+        asset_base_url: "https://cdn.discordapp.com".to_string(),
+        asset_max_bytes: 1024 * 1024,
+        asset_retry_backoff_seconds: 300,
+        asset_rewrite_urls: false,
+        admin_token: None,
+    }
+}
+
+pub fn test_state(db: MySqlPool, config: Config) -> AppState {
+    let source = sources::from_config(&config).unwrap();
+    AppState::new(db, config, source)
+}
+
+/// Serve the full application on a random local port and return its base URL
+pub async fn spawn_app(db: MySqlPool, config: Config) -> String {
+    serve(test_state(db, config)).await
+}
+
+/// Serve an existing state on a random local port and return its base URL
+pub async fn serve(state: AppState) -> String {
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, build_router(state)).await.unwrap();
+    });
+
+    format!("http://{}", addr)
+}
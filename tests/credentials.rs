@@ -0,0 +1,135 @@
+//! Rejected credentials and picking up a replacement, against the mock
+//! Discord server. No database needed.
+
+mod common;
+
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+
+use kythia_quest_api::{
+    mock_discord::{default_payload, MockDiscord, MockMode},
+    sources::DiscordSource,
+    utils::{
+        credentials::{read_secret_file, TokenProvider, TokenSource},
+        discord::build_http_client,
+        error::ApiError,
+        upstream_guard::{AuthState, CircuitState, UpstreamGuard, UpstreamPolicy},
+    },
+};
+
+async fn start(token: Arc<TokenProvider>) -> (MockDiscord, DiscordSource) {
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap();
+    let client = build_http_client(Duration::from_secs(1), Duration::from_secs(1)).unwrap();
+    let source = DiscordSource::new(client, &mock.quests_url(), token);
+    (mock, source)
+}
+
+fn guard() -> UpstreamGuard {
+    UpstreamGuard::new(UpstreamPolicy {
+        max_retries: 2,
+        retry_backoff: Duration::from_millis(10),
+        breaker_threshold: 1,
+        breaker_cooldown: Duration::from_secs(60),
+        max_rate_limit_pause: Duration::from_secs(3600),
+    })
+}
+
+fn token_file(name: &str, token: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    std::fs::write(&path, token).unwrap();
+    path
+}
+
+#[tokio::test]
+async fn successful_fetch_marks_credential_valid() {
+    let (_mock, source) = start(Arc::new(TokenProvider::new(
+        "token",
+        TokenSource::Environment,
+    )))
+    .await;
+    let guard = guard();
+    assert_eq!(guard.status().auth, AuthState::Unknown);
+
+    guard.fetch(&source).await.unwrap();
+
+    assert_eq!(guard.status().auth, AuthState::Valid);
+}
+
+#[tokio::test]
+async fn rejected_credential_stops_upstream_calls() {
+    let path = token_file("kythia-rejected-token", "revoked");
+    let token = Arc::new(TokenProvider::new(
+        "revoked",
+        TokenSource::File(path.clone()),
+    ));
+    let (mock, source) = start(token).await;
+    let guard = guard();
+    mock.set_mode(MockMode::Unauthorized);
+
+    assert_eq!(guard.fetch(&source).await.unwrap().status, 401);
+
+    let status = guard.status();
+    assert_eq!(status.auth, AuthState::Invalid);
+    // Not an upstream outage: the breaker stays closed
+    assert_eq!(status.circuit, CircuitState::Closed);
+    assert_eq!(status.consecutive_failures, 0);
+
+    // Until the credential changes upstream is not called again
+    mock.set_mode(MockMode::Ok);
+    let err = guard.fetch(&source).await.unwrap_err();
+    assert!(matches!(err, ApiError::UpstreamAuthError(_)));
+    assert_eq!(mock.request_count(), 1);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn replaced_token_file_is_picked_up() {
+    let path = token_file("kythia-replaced-token", "revoked");
+    let token = Arc::new(TokenProvider::new(
+        "revoked",
+        TokenSource::File(path.clone()),
+    ));
+    let (mock, source) = start(token.clone()).await;
+    let guard = guard();
+    mock.set_mode(MockMode::Unauthorized);
+    guard.fetch(&source).await.unwrap();
+
+    mock.set_mode(MockMode::Ok);
+    std::fs::write(&path, "rotated\n").unwrap();
+
+    assert_eq!(guard.fetch(&source).await.unwrap().status, 200);
+    assert_eq!(token.current().expose(), "rotated");
+    assert_eq!(guard.status().auth, AuthState::Valid);
+    assert_eq!(mock.request_count(), 2);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn process_environment_token_wins_over_dotenv() {
+    let mut config = common::test_config("http://127.0.0.1:1/quests".to_string());
+    assert_eq!(config.discord_token_source(), TokenSource::Environment);
+
+    // Nothing to re-read: the environment cannot change while running
+    let token = TokenProvider::new("from-environment", config.discord_token_source());
+    assert!(!token.reload().await);
+    assert_eq!(token.current().expose(), "from-environment");
+
+    config.discord_token_in_environment = false;
+    assert_eq!(config.discord_token_source(), TokenSource::DotEnv);
+
+    let path = PathBuf::from("/run/secrets/discord_token");
+    config.discord_token_file = Some(path.clone());
+    assert_eq!(config.discord_token_source(), TokenSource::File(path));
+}
+
+#[test]
+fn token_files_are_trimmed() {
+    let path = token_file("discord-token-newline", "  file-token\n");
+
+    assert_eq!(read_secret_file(&path).unwrap().expose(), "file-token");
+
+    std::fs::remove_file(path).ok();
+}
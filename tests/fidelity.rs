@@ -0,0 +1,224 @@
+//! Golden tests for the fidelity contract: every quest in the captured
+//! payloads under `tests/fixtures/golden` (and the mock server fixture) must
+//! reconstruct to the object Discord sent, apart from the differences allowed
+//! in `utils::fidelity`. The database round-trip is skipped unless
+//! `TEST_DATABASE_URL` is set.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use kythia_quest_api::{
+    db::quest_operations::get_complete_quest_by_id,
+    mock_discord::default_payload,
+    utils::{
+        fidelity::fidelity_violations,
+        quest_parser::{parse_single_quest, reconstruct_single_quest, save_discord_quests_to_db},
+    },
+};
+use serde_json::Value;
+
+use common::{test_pool, DB_LOCK};
+
+/// Every golden payload, with a name for failure messages
+fn golden_payloads() -> Vec<(String, Value)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut payloads: Vec<(String, Value)> = files
+        .into_iter()
+        .map(|path| {
+            let contents = std::fs::read_to_string(&path).unwrap();
+            (
+                path.file_name().unwrap().to_string_lossy().into_owned(),
+                serde_json::from_str(&contents).unwrap(),
+            )
+        })
+        .collect();
+    payloads.push(("mock_discord/quests.json".to_string(), default_payload()));
+
+    payloads
+}
+
+fn quests(payload: &Value) -> &Vec<Value> {
+    payload["quests"].as_array().unwrap()
+}
+
+#[test]
+fn golden_quests_reconstruct_faithfully() {
+    for (name, payload) in golden_payloads() {
+        for raw in quests(&payload) {
+            let (quest, _) = parse_single_quest(raw).unwrap();
+            let reconstructed = reconstruct_single_quest(&quest);
+
+            let violations = fidelity_violations(raw, &reconstructed);
+            assert!(
+                violations.is_empty(),
+                "{} quest {} drifted: {:#?}",
+                name,
+                raw["id"],
+                violations
+            );
+        }
+    }
+}
+
+#[test]
+fn keys_discord_left_out_are_not_invented() {
+    let raw = &golden_payloads()
+        .into_iter()
+        .find(|(name, _)| name == "sparse_collectible.json")
+        .unwrap()
+        .1["quests"][0];
+
+    let (quest, _) = parse_single_quest(raw).unwrap();
+    let reconstructed = reconstruct_single_quest(&quest);
+
+    assert!(reconstructed["config"].get("id").is_none());
+    assert!(reconstructed.get("user_status").is_none());
+    assert!(reconstructed["config"].get("cta_config").is_none());
+    let task = &reconstructed["config"]["task_config_v2"]["tasks"]["WATCH_VIDEO_ON_MOBILE"];
+    assert!(task.get("type").is_none());
+    assert!(task.get("applications").is_none());
+}
+
+#[test]
+fn legacy_task_config_is_parsed_and_kept_in_its_schema() {
+    let payload = &golden_payloads()
+        .into_iter()
+        .find(|(name, _)| name == "legacy_task_config.json")
+        .unwrap()
+        .1;
+
+    // v1 only: tasks come from `task_config`
+    let (legacy, unknown_fields) = parse_single_quest(&payload["quests"][0]).unwrap();
+    assert_eq!(legacy.quest.task_config_version, Some(1));
+    let kinds: Vec<&str> = legacy.tasks.iter().map(|t| t.task_type.as_str()).collect();
+    assert_eq!(kinds, ["PLAY_ON_DESKTOP", "STREAM_ON_DESKTOP"]);
+    assert_eq!(legacy.tasks[0].target, 900);
+    assert!(!unknown_fields
+        .iter()
+        .any(|field| field.path.starts_with("config.task_config.tasks")));
+
+    let reconstructed = reconstruct_single_quest(&legacy);
+    assert!(reconstructed["config"].get("task_config_v2").is_none());
+    assert_eq!(
+        reconstructed["config"]["task_config"]["tasks"]["PLAY_ON_DESKTOP"]["event_name"],
+        "PLAY_ON_DESKTOP"
+    );
+
+    // Both layouts: tasks come from v2, v1 is kept as received
+    let (both, _) = parse_single_quest(&payload["quests"][1]).unwrap();
+    assert_eq!(both.quest.task_config_version, Some(2));
+    assert!(both.tasks[0].applications.is_some());
+}
+
+#[test]
+fn odd_tasks_are_kept_instead_of_failing_the_quest() {
+    let payload = default_payload();
+    let mut raw = quests(&payload)[1].clone();
+    let tasks = &mut raw["config"]["task_config_v2"]["tasks"];
+    tasks["PLAY_ON_DESKTOP"]["target"] = serde_json::json!(900.5);
+    tasks["PLAY_ON_XBOX"]["target"] = serde_json::json!(5_000_000_000_i64);
+    tasks["SOMETHING_NEW"] = Value::from("not an object");
+
+    let (quest, unknown_fields) = parse_single_quest(&raw).unwrap();
+
+    // Fractions and overflows are stored best-effort, the odd entry not at all
+    let targets: Vec<(&str, i32)> = quest
+        .tasks
+        .iter()
+        .map(|task| (task.task_type.as_str(), task.target))
+        .collect();
+    assert_eq!(
+        targets,
+        [("PLAY_ON_DESKTOP", 900), ("PLAY_ON_XBOX", i32::MAX)]
+    );
+    let paths: Vec<&str> = unknown_fields
+        .iter()
+        .map(|field| field.path.as_str())
+        .collect();
+    assert!(paths.contains(&"config.task_config_v2.tasks.*.target"));
+    assert!(paths.contains(&"config.task_config_v2.tasks.SOMETHING_NEW"));
+
+    // ...and all of them reconstruct as received
+    let reconstructed = reconstruct_single_quest(&quest);
+    assert!(fidelity_violations(&raw, &reconstructed).is_empty());
+    assert_eq!(
+        reconstructed["config"]["task_config_v2"]["tasks"],
+        raw["config"]["task_config_v2"]["tasks"]
+    );
+}
+
+#[test]
+fn user_status_and_timestamp_format_are_allowed_differences() {
+    let payload = default_payload();
+    let mut raw = quests(&payload)[0].clone();
+    raw["user_status"] = serde_json::json!({ "enrolled_at": "2025-12-02T00:00:00+00:00" });
+    raw["config"]["starts_at"] = Value::from("2025-12-01T16:00:00.250Z");
+
+    let (quest, _) = parse_single_quest(&raw).unwrap();
+    let reconstructed = reconstruct_single_quest(&quest);
+
+    assert_eq!(reconstructed["user_status"], Value::Null);
+    assert_eq!(
+        reconstructed["config"]["starts_at"],
+        "2025-12-01T16:00:00+00:00"
+    );
+    assert!(fidelity_violations(&raw, &reconstructed).is_empty());
+}
+
+#[test]
+fn modelled_value_changes_are_violations() {
+    let payload = default_payload();
+    let raw = quests(&payload)[0].clone();
+    let (quest, _) = parse_single_quest(&raw).unwrap();
+    let mut reconstructed = reconstruct_single_quest(&quest);
+    reconstructed["config"]["rewards_config"]["platforms"] = serde_json::json!([0, 1]);
+
+    let violations = fidelity_violations(&raw, &reconstructed);
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, "config.rewards_config.platforms[1]");
+}
+
+#[tokio::test]
+async fn golden_quests_survive_the_database() {
+    let _guard = DB_LOCK.lock().await;
+    let Some(pool) = test_pool().await else {
+        return;
+    };
+
+    for (name, payload) in golden_payloads() {
+        save_discord_quests_to_db(&pool, &payload).await.unwrap();
+
+        for raw in quests(&payload) {
+            let id = raw["id"].as_str().unwrap();
+            let stored = get_complete_quest_by_id(&pool, id)
+                .await
+                .unwrap()
+                .expect("golden quest was not stored");
+            let reconstructed = reconstruct_single_quest(&stored);
+
+            let violations = fidelity_violations(raw, &reconstructed);
+            assert!(
+                violations.is_empty(),
+                "{} quest {} drifted through the database: {:#?}",
+                name,
+                id,
+                violations
+            );
+
+            sqlx::query("DELETE FROM quests WHERE id = ?")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Decoding of reward platform ids. No database needed.
+
+use kythia_quest_api::utils::platform::Platform;
+
+#[test]
+fn ids_decode_to_names() {
+    let names: Vec<String> = (0..=5)
+        .map(|id| Platform::from_id(id).to_string())
+        .collect();
+
+    assert_eq!(
+        names,
+        [
+            "CROSS_PLATFORM",
+            "XBOX",
+            "PLAYSTATION",
+            "SWITCH",
+            "PC",
+            "UNKNOWN_5"
+        ]
+    );
+}
+
+#[test]
+fn names_and_ids_parse() {
+    assert_eq!("xbox".parse(), Ok(Platform::Xbox));
+    assert_eq!("Cross-Platform".parse(), Ok(Platform::CrossPlatform));
+    assert_eq!("4".parse(), Ok(Platform::Pc));
+    assert_eq!("9".parse(), Ok(Platform::Unknown(9)));
+    assert!("dreamcast".parse::<Platform>().is_err());
+}
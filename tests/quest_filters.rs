@@ -0,0 +1,79 @@
+//! Parsing and cache keys of the quest list filters. No database needed.
+
+use kythia_quest_api::utils::{
+    platform::Platform,
+    quest_filter::{parse_bool, parse_count, QuestFilter, QuestStatus},
+    refresh::QUEST_CACHE_KEY,
+    reward_kind::RewardCategory,
+};
+
+#[test]
+fn statuses_parse() {
+    assert_eq!("active".parse(), Ok(QuestStatus::Active));
+    assert_eq!("Upcoming".parse(), Ok(QuestStatus::Upcoming));
+    assert_eq!(" expired ".parse(), Ok(QuestStatus::Expired));
+    assert!("archived".parse::<QuestStatus>().is_err());
+}
+
+#[test]
+fn unfiltered_list_keeps_the_main_cache_key() {
+    assert!(QuestFilter::default().is_empty());
+    assert_eq!(QuestFilter::default().cache_key(), QUEST_CACHE_KEY);
+}
+
+#[test]
+fn each_filter_combination_has_its_own_key() {
+    let xbox = QuestFilter {
+        platform: Some(Platform::Xbox),
+        ..Default::default()
+    };
+    let xbox_orbs = QuestFilter {
+        reward_type: Some(RewardCategory::Orbs),
+        ..xbox.clone()
+    };
+
+    assert!(xbox
+        .cache_key()
+        .starts_with(&format!("{}?", QUEST_CACHE_KEY)));
+    assert_eq!(xbox.cache_key(), xbox.clone().cache_key());
+    assert_ne!(xbox.cache_key(), xbox_orbs.cache_key());
+    assert_ne!(
+        xbox.cache_key(),
+        QuestFilter {
+            age_days: Some(30),
+            ..xbox.clone()
+        }
+        .cache_key()
+    );
+}
+
+#[test]
+fn publisher_keys_ignore_case() {
+    let publisher = |name: &str| QuestFilter {
+        publisher: Some(name.to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        publisher("Orbit Forge").cache_key(),
+        publisher("orbit forge").cache_key()
+    );
+}
+
+#[test]
+fn values_are_validated() {
+    assert_eq!(parse_bool("preview", "false"), Ok(false));
+    assert_eq!(parse_bool("preview", "1"), Ok(true));
+    assert!(parse_bool("preview", "maybe").is_err());
+
+    assert_eq!(parse_count::<i32>("min_orbs", "500"), Ok(500));
+    assert!(parse_count::<i32>("min_orbs", "-1").is_err());
+    assert!(parse_count::<i64>("age_days", "a week").is_err());
+}
+
+#[test]
+fn reward_categories_map_to_discord_types() {
+    assert_eq!(RewardCategory::Orbs.reward_type(), Some(4));
+    assert_eq!(RewardCategory::Collectible.reward_type(), Some(3));
+    assert_eq!(RewardCategory::Unknown.reward_type(), None);
+}
@@ -0,0 +1,79 @@
+//! Change detection on re-ingest. Needs a MySQL database, so it is skipped
+//! unless `TEST_DATABASE_URL` is set.
+
+use kythia_quest_api::{
+    db::quest_operations::{get_complete_quest_by_id, get_quest_revisions},
+    mock_discord::default_payload,
+    utils::quest_parser::{
+        apply_ingest_plan, plan_discord_quests, save_discord_quests_to_db, QuestAction,
+    },
+};
+use serde_json::{json, Value};
+use sqlx::MySqlPool;
+
+const QUEST_ID: &str = "9000000000000000001";
+
+/// The first fixture quest under an id no other test uses
+fn quest_response() -> Value {
+    let mut quest = default_payload()["quests"][0].clone();
+    quest["id"] = json!(QUEST_ID);
+    quest["config"]["id"] = json!(QUEST_ID);
+
+    json!({ "quests": [quest], "excluded_quests": [] })
+}
+
+#[tokio::test]
+async fn changed_config_is_planned_as_an_update_and_written() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("skipping: TEST_DATABASE_URL is not set");
+        return;
+    };
+
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let original = quest_response();
+    save_discord_quests_to_db(&pool, &original).await.unwrap();
+
+    // Re-ingesting the same payload is a no-op
+    let plan = plan_discord_quests(&pool, &original).await.unwrap();
+    assert!(matches!(plan.quests[0].action, QuestAction::Unchanged));
+
+    let mut changed = original.clone();
+    changed["quests"][0]["config"]["expires_at"] = json!("2099-06-01T00:00:00+00:00");
+
+    let plan = plan_discord_quests(&pool, &changed).await.unwrap();
+    let QuestAction::Update { changes, .. } = &plan.quests[0].action else {
+        panic!("expected an update, got {:?}", plan.quests[0].action);
+    };
+    assert!(
+        changes
+            .iter()
+            .any(|change| change.path == "config.expires_at"),
+        "{:?}",
+        changes
+    );
+
+    let summary = apply_ingest_plan(&pool, &plan).await.unwrap();
+    assert_eq!(summary.updated, 1);
+
+    let stored = get_complete_quest_by_id(&pool, QUEST_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stored.quest.expires_at.to_rfc3339(),
+        "2099-06-01T00:00:00+00:00"
+    );
+
+    // The insert and the update each left one revision, written with the quest
+    let revisions = get_quest_revisions(&pool, QUEST_ID).await.unwrap();
+    let numbers: Vec<i32> = revisions.iter().map(|r| r.revision).collect();
+    assert_eq!(numbers, [1, 2]);
+
+    sqlx::query("DELETE FROM quests WHERE id = ?")
+        .bind(QUEST_ID)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
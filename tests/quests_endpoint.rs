@@ -0,0 +1,654 @@
+//! End-to-end tests for `GET /v1/quests` against the mock Discord server.
+//! Skipped unless `TEST_DATABASE_URL` points at a scratch MySQL database.
+
+mod common;
+
+use std::{net::SocketAddr, time::Duration};
+
+use kythia_quest_api::{
+    config::RefreshMode,
+    db::operations::delete_cache_variants,
+    mock_discord::{default_payload, MockDiscord, MockMode},
+    utils::{refresh::QUEST_CACHE_KEY, scheduler::RefreshScheduler},
+};
+use serde_json::Value;
+use sqlx::MySqlPool;
+
+use common::{serve, spawn_app, test_config, test_pool, test_state, DB_LOCK};
+
+/// Remove everything the fixture payload may have left behind
+async fn reset(pool: &MySqlPool) {
+    let payload = default_payload();
+
+    for quest in payload["quests"].as_array().unwrap() {
+        sqlx::query("DELETE FROM quests WHERE id = ?")
+            .bind(quest["id"].as_str().unwrap())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    for excluded in payload["excluded_quests"].as_array().unwrap() {
+        sqlx::query("DELETE FROM excluded_quests WHERE id = ?")
+            .bind(excluded["id"].as_str().unwrap())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    // Otherwise the next fetch of the same payload would skip ingest
+    sqlx::query("DELETE FROM upstream_payloads")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    clear_cache(pool).await;
+}
+
+async fn clear_cache(pool: &MySqlPool) {
+    sqlx::query("DELETE FROM cache_store WHERE id = ?")
+        .bind(QUEST_CACHE_KEY)
+        .execute(pool)
+        .await
+        .unwrap();
+    delete_cache_variants(pool, QUEST_CACHE_KEY).await.unwrap();
+}
+
+async fn start() -> Option<(MySqlPool, MockDiscord, String)> {
+    let pool = test_pool().await?;
+    reset(&pool).await;
+
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap();
+    let app = spawn_app(pool.clone(), test_config(mock.quests_url())).await;
+
+    Some((pool, mock, app))
+}
+
+async fn get_quests(app: &str) -> (u16, Value) {
+    get_json(app, "/v1/quests").await
+}
+
+/// Status and JSON body of `GET <app><path>`; `Null` when the body is not JSON
+async fn get_json(app: &str, path: &str) -> (u16, Value) {
+    let response = reqwest::get(format!("{}{}", app, path)).await.unwrap();
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or(Value::Null))
+}
+
+fn quest_ids(body: &Value) -> Vec<&str> {
+    body["quests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|quest| quest["id"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn serves_fixture_quests_and_caches_them() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    let ids = quest_ids(&body);
+    assert!(ids.contains(&"1412491570820812933"));
+    assert!(ids.contains(&"1419012345678901234"));
+    assert_eq!(mock.request_count(), 1);
+    assert_eq!(
+        body["quests"][0]["_annotations"]["tasks"]["items"][0]["description"],
+        "Watch the quest video for 15 minutes"
+    );
+
+    // A fresh cache answers without calling upstream again
+    let (status, cached) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(cached, body);
+    assert_eq!(mock.request_count(), 1);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn upstream_failures_surface_as_bad_gateway() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, _)) = start().await else {
+        return;
+    };
+
+    // (mode, upstream calls, error, error of a second request that must not reach upstream)
+    let cases = [
+        (
+            MockMode::Unauthorized,
+            1,
+            "401 Unauthorized",
+            Some("waiting for it to be replaced"),
+        ),
+        (
+            MockMode::RateLimited {
+                retry_after_secs: 60,
+            },
+            1,
+            "429 Too Many Requests",
+            Some("Rate limited by upstream"),
+        ),
+        (
+            MockMode::ServerError(500),
+            2,
+            "500 Internal Server Error",
+            None,
+        ),
+        (MockMode::Timeout, 2, "Request failed", None),
+    ];
+
+    for (mode, calls, error, follow_up) in cases {
+        // A fresh app per mode, so the guard state of one mode cannot mask the next
+        let mut config = test_config(mock.quests_url());
+        config.upstream_max_retries = 1;
+        let app = spawn_app(pool.clone(), config).await;
+        mock.set_mode(mode.clone());
+
+        let before = mock.request_count();
+        let (status, body) = get_quests(&app).await;
+        assert_eq!(status, 502, "{:?}: {}", mode, body);
+        assert_eq!(body["status"], 502);
+        assert!(
+            body["error"].as_str().unwrap().contains(error),
+            "{:?}: {}",
+            mode,
+            body
+        );
+        assert_eq!(mock.request_count() - before, calls, "{:?}", mode);
+
+        if let Some(follow_up) = follow_up {
+            let before = mock.request_count();
+            let (status, body) = get_quests(&app).await;
+            assert_eq!(status, 502, "{:?}: {}", mode, body);
+            assert!(
+                body["error"].as_str().unwrap().contains(follow_up),
+                "{:?}: {}",
+                mode,
+                body
+            );
+            assert_eq!(mock.request_count(), before, "{:?}", mode);
+        }
+    }
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn slow_upstream_still_fills_the_cache() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    mock.set_mode(MockMode::Slow(Duration::from_millis(500)));
+
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(quest_ids(&body).len(), 2);
+
+    // Recovering after a failure serves the stored quests again
+    mock.set_mode(MockMode::ServerError(503));
+    clear_cache(&pool).await;
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 502);
+
+    mock.set_mode(MockMode::Ok);
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(quest_ids(&body).len(), 2);
+
+    reset(&pool).await;
+}
+
+/// Pretend the cached response was written `minutes` ago
+async fn age_cache(pool: &MySqlPool, minutes: i64) {
+    sqlx::query(
+        "UPDATE cache_store SET updated_at = UTC_TIMESTAMP() - INTERVAL ? MINUTE WHERE id = ?",
+    )
+    .bind(minutes)
+    .bind(QUEST_CACHE_KEY)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn stale_cache_is_served_when_upstream_fails() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, fresh) = get_quests(&app).await;
+    assert_eq!(status, 200);
+
+    // Expired (30 min) but within the 60 minute staleness allowance
+    mock.set_mode(MockMode::ServerError(500));
+    age_cache(&pool, 45).await;
+
+    let response = reqwest::get(format!("{}/v1/quests", app)).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-cache-status"], "STALE");
+    assert!(response.headers().contains_key("warning"));
+    let age: i64 = response.headers()["age"].to_str().unwrap().parse().unwrap();
+    assert!(age >= 45 * 60, "age {}", age);
+    assert_eq!(response.json::<Value>().await.unwrap(), fresh);
+
+    // The refresh is retried in the background
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(mock.request_count(), 2);
+
+    // Past the allowance the upstream error is returned
+    age_cache(&pool, 120).await;
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 502);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn unchanged_payloads_keep_excluded_quests_listed() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 200);
+
+    // Last reported long ago, but the next (identical) payload still lists it
+    sqlx::query(
+        "UPDATE excluded_quests SET last_seen_at = UTC_TIMESTAMP() - INTERVAL 40 DAY WHERE id = ?",
+    )
+    .bind("1401000000000000001")
+    .execute(&pool)
+    .await
+    .unwrap();
+    clear_cache(&pool).await;
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(mock.request_count(), 2);
+
+    let (_, body) = get_json(&app, "/v1/quests/excluded").await;
+    let ids: Vec<&str> = body["excluded_quests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|excluded| excluded["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["1401000000000000001"]);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn parallel_requests_on_a_stale_cache_make_one_upstream_call() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(mock.request_count(), 1);
+
+    // Past the staleness allowance, so every request needs the refresh to finish
+    age_cache(&pool, 120).await;
+
+    // Widen the window so every request arrives while the refresh is running
+    mock.set_mode(MockMode::Slow(Duration::from_millis(500)));
+
+    let requests: Vec<_> = (0..20)
+        .map(|_| {
+            let app = app.clone();
+            tokio::spawn(async move { get_quests(&app).await })
+        })
+        .collect();
+
+    for request in requests {
+        let (status, body) = request.await.unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(quest_ids(&body).len(), 2);
+    }
+    assert_eq!(mock.request_count(), 2);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn background_mode_only_reads_the_cache() {
+    let _guard = DB_LOCK.lock().await;
+    let Some(pool) = test_pool().await else {
+        return;
+    };
+    reset(&pool).await;
+
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap();
+    let mut config = test_config(mock.quests_url());
+    config.refresh_mode = RefreshMode::Background;
+    config.cache_duration_minutes = 0;
+    config.refresh_min_interval_seconds = 1;
+    config.refresh_jitter_percent = 0;
+    let state = test_state(pool.clone(), config);
+    let app = serve(state.clone()).await;
+
+    // Nothing cached yet, and the request must not fetch upstream itself
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 503, "{}", body);
+    assert_eq!(mock.request_count(), 0);
+
+    let scheduler = RefreshScheduler::spawn(state, false);
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(quest_ids(&body).len(), 2);
+    assert!(mock.request_count() >= 1);
+
+    // Stopping is prompt and no further refreshes happen afterwards
+    tokio::time::timeout(Duration::from_secs(5), scheduler.stop())
+        .await
+        .expect("scheduler did not stop");
+    let stopped_at = mock.request_count();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(mock.request_count(), stopped_at);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn reward_platforms_round_trip_and_filter() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, _mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    let quest = body["quests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|quest| quest["id"] == "1419012345678901234")
+        .unwrap();
+    assert_eq!(
+        quest["config"]["rewards_config"]["platforms"],
+        serde_json::json!([1, 2])
+    );
+    assert_eq!(
+        quest["_annotations"]["platform_names"],
+        serde_json::json!(["XBOX", "PLAYSTATION"])
+    );
+
+    // Cross-platform quests are available everywhere
+    let (status, body) = get_json(&app, "/v1/quests?platform=xbox").await;
+    assert_eq!(status, 200);
+    assert_eq!(
+        quest_ids(&body),
+        vec!["1412491570820812933", "1419012345678901234"]
+    );
+
+    let (_, body) = get_json(&app, "/v1/quests?platform=pc").await;
+    assert_eq!(quest_ids(&body), vec!["1412491570820812933"]);
+
+    let (_, body) = get_json(&app, "/v1/quests?platform=2").await;
+    assert_eq!(quest_ids(&body).len(), 2);
+
+    let (status, _) = get_json(&app, "/v1/quests?platform=dreamcast").await;
+    assert_eq!(status, 400);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn rewards_are_decoded_and_filterable() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, _mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, body) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    let quest = body["quests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|quest| quest["id"] == "1412491570820812933")
+        .unwrap();
+    assert_eq!(
+        quest["_annotations"]["rewards"],
+        serde_json::json!([{
+            "type": 4,
+            "category": "orbs",
+            "label": "Orbs",
+            "name": "700 Orbs",
+            "orb_quantity": 700
+        }])
+    );
+
+    let (status, body) = get_json(&app, "/v1/quests?reward=orbs").await;
+    assert_eq!(status, 200);
+    assert_eq!(quest_ids(&body), vec!["1412491570820812933"]);
+
+    let (_, body) = get_json(&app, "/v1/quests?reward=collectible").await;
+    assert_eq!(quest_ids(&body), vec!["1419012345678901234"]);
+
+    let (_, body) = get_json(&app, "/v1/quests?reward=in_game").await;
+    assert!(quest_ids(&body).is_empty());
+
+    let (status, _) = get_json(&app, "/v1/quests?reward=gold").await;
+    assert_eq!(status, 400);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn resolved_format_serves_absolute_asset_urls() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, _mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, body) = get_json(
+        &app,
+        "/v1/quests?format=resolved&asset_size=512&asset_format=webp",
+    )
+    .await;
+    assert_eq!(status, 200);
+    let assets = &body["quests"][0]["config"]["assets"];
+    assert!(assets["hero"]
+        .as_str()
+        .unwrap()
+        .starts_with("https://cdn.discordapp.com/quests/"));
+    assert!(assets["hero"]
+        .as_str()
+        .unwrap()
+        .ends_with("?format=webp&size=512"));
+
+    // The default format keeps Discord's keys
+    let (_, body) = get_json(&app, "/v1/quests?format=discord").await;
+    assert!(body["quests"][0]["config"]["assets"]["hero"]
+        .as_str()
+        .unwrap()
+        .starts_with("quests/"));
+
+    let (status, _) = get_json(&app, "/v1/quests?format=resolved&asset_size=1000").await;
+    assert_eq!(status, 400);
+    let (status, _) = get_json(&app, "/v1/quests?format=compact").await;
+    assert_eq!(status, 400);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn single_quest_lookup_ignores_the_age_window() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, _mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, list) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    let listed = list["quests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|quest| quest["id"] == "1419012345678901234")
+        .unwrap()
+        .clone();
+
+    // Same shape as the list entry
+    let (status, quest) = get_json(&app, "/v1/quests/1419012345678901234").await;
+    assert_eq!(status, 200);
+    assert_eq!(quest, listed);
+
+    let (status, body) = get_json(&app, "/v1/quests/1").await;
+    assert_eq!(status, 404);
+    assert_eq!(body["status"], 404);
+
+    // Revisions answer the same way
+    let (status, body) = get_json(&app, "/v1/quests/1419012345678901234/revisions").await;
+    assert_eq!(status, 200);
+    assert_eq!(body["revisions"].as_array().unwrap().len(), 1);
+    let (status, _) = get_json(&app, "/v1/quests/1/revisions").await;
+    assert_eq!(status, 404);
+
+    // Long expired quests drop out of the list but still resolve by id
+    sqlx::query("UPDATE quests SET expires_at = '2020-01-01 00:00:00' WHERE id = ?")
+        .bind("1419012345678901234")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, quest) = get_json(&app, "/v1/quests/1419012345678901234").await;
+    assert_eq!(status, 200);
+    assert_eq!(quest["config"]["expires_at"], "2020-01-01T00:00:00+00:00");
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn filters_run_in_sql_and_are_cached_separately() {
+    let _guard = DB_LOCK.lock().await;
+    let Some((pool, mock, app)) = start().await else {
+        return;
+    };
+
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 200);
+
+    let cases: [(&str, Vec<&str>); 8] = [
+        (
+            "status=active",
+            vec!["1412491570820812933", "1419012345678901234"],
+        ),
+        ("status=upcoming", vec![]),
+        (
+            "application_id=1385123456789012345",
+            vec!["1419012345678901234"],
+        ),
+        ("publisher=orbit%20forge", vec!["1412491570820812933"]),
+        ("task_type=play_on_desktop", vec!["1419012345678901234"]),
+        ("reward_type=orbs&min_orbs=500", vec!["1412491570820812933"]),
+        ("min_orbs=1000", vec![]),
+        ("preview=false&platform=pc", vec!["1412491570820812933"]),
+    ];
+    for (query, expected) in cases {
+        let (status, body) = get_json(&app, &format!("/v1/quests?{}", query)).await;
+        assert_eq!(status, 200, "{}", query);
+        assert_eq!(quest_ids(&body), expected, "{}", query);
+    }
+
+    for query in [
+        "status=archived",
+        "min_orbs=-5",
+        "preview=maybe",
+        "age_days=soon",
+    ] {
+        let (status, _) = get_json(&app, &format!("/v1/quests?{}", query)).await;
+        assert_eq!(status, 400, "{}", query);
+    }
+
+    // Filtered variants live under their own keys and are dropped on refresh
+    let variants = || async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM cache_store WHERE id LIKE ?")
+            .bind(format!("{}?%", QUEST_CACHE_KEY))
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+    assert!(variants().await >= 8);
+    let requests = mock.request_count();
+    let (_, body) = get_json(&app, "/v1/quests?status=active").await;
+    assert_eq!(quest_ids(&body).len(), 2);
+    assert_eq!(mock.request_count(), requests);
+
+    // A variant built from an older full list is rebuilt, however recently it was written
+    sqlx::query(
+        "UPDATE cache_store SET data = JSON_OBJECT('quests', JSON_ARRAY()), \
+         source_updated_at = source_updated_at - INTERVAL 1 HOUR, updated_at = UTC_TIMESTAMP() \
+         WHERE id LIKE ?",
+    )
+    .bind(format!("{}?%", QUEST_CACHE_KEY))
+    .execute(&pool)
+    .await
+    .unwrap();
+    let (_, body) = get_json(&app, "/v1/quests?status=active").await;
+    assert_eq!(quest_ids(&body).len(), 2);
+
+    // Only the full list is removed, so the refresh it triggers drops the variants
+    sqlx::query("DELETE FROM cache_store WHERE id = ?")
+        .bind(QUEST_CACHE_KEY)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (status, _) = get_quests(&app).await;
+    assert_eq!(status, 200);
+    assert_eq!(variants().await, 0);
+
+    reset(&pool).await;
+}
+
+#[tokio::test]
+async fn filtered_variants_are_bounded() {
+    let _guard = DB_LOCK.lock().await;
+    let Some(pool) = test_pool().await else {
+        return;
+    };
+    reset(&pool).await;
+
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap();
+    let mut config = test_config(mock.quests_url());
+    config.cache_max_variants = 2;
+    let app = spawn_app(pool.clone(), config).await;
+
+    for query in [
+        "publisher=a",
+        "publisher=b",
+        "publisher=c",
+        "application_id=1",
+    ] {
+        let (status, _) = get_json(&app, &format!("/v1/quests?{}", query)).await;
+        assert_eq!(status, 200, "{}", query);
+    }
+
+    let variants: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cache_store WHERE id LIKE ?")
+        .bind(format!("{}?%", QUEST_CACHE_KEY))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(variants, 2);
+
+    reset(&pool).await;
+}
@@ -0,0 +1,62 @@
+//! Decoding of reward types. No database needed.
+
+use kythia_quest_api::{
+    db::quest_models::QuestReward,
+    utils::reward_kind::{RewardCategory, RewardKind, RewardSummary},
+};
+
+#[test]
+fn ids_decode_to_categories_and_labels() {
+    let decoded: Vec<(String, String)> = (1..=6)
+        .map(|id| {
+            let kind = RewardKind::from_id(id);
+            (kind.category().to_string(), kind.label())
+        })
+        .collect();
+
+    assert_eq!(
+        decoded,
+        [
+            ("reward_code".to_string(), "Reward code".to_string()),
+            ("in_game".to_string(), "In-game item".to_string()),
+            ("collectible".to_string(), "Collectible".to_string()),
+            ("orbs".to_string(), "Orbs".to_string()),
+            ("nitro".to_string(), "Nitro trial".to_string()),
+            ("unknown".to_string(), "Unknown reward type 6".to_string()),
+        ]
+    );
+    assert_eq!(RewardKind::from_id(6).id(), 6);
+}
+
+#[test]
+fn categories_and_ids_parse() {
+    assert_eq!("orbs".parse(), Ok(RewardCategory::Orbs));
+    assert_eq!("In-Game".parse(), Ok(RewardCategory::InGame));
+    assert_eq!("3".parse(), Ok(RewardCategory::Collectible));
+    assert_eq!("42".parse(), Ok(RewardCategory::Unknown));
+    assert!("gold".parse::<RewardCategory>().is_err());
+}
+
+#[test]
+fn summary_keeps_the_reward_name() {
+    let reward = QuestReward {
+        id: 1,
+        quest_id: "1".to_string(),
+        reward_type: 3,
+        sku_id: Some("2".to_string()),
+        reward_name: "Royal Banner Avatar Decoration".to_string(),
+        reward_name_with_article: "a Royal Banner Avatar Decoration".to_string(),
+        orb_quantity: None,
+        redemption_instructions: None,
+    };
+
+    assert_eq!(
+        serde_json::to_value(RewardSummary::from_reward(&reward)).unwrap(),
+        serde_json::json!({
+            "type": 3,
+            "category": "collectible",
+            "label": "Collectible",
+            "name": "Royal Banner Avatar Decoration"
+        })
+    );
+}
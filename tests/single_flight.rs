@@ -0,0 +1,72 @@
+//! Coalescing of concurrent refreshes. No database needed.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use kythia_quest_api::utils::single_flight::SingleFlight;
+
+#[tokio::test]
+async fn parallel_callers_share_one_call() {
+    let flight = Arc::new(SingleFlight::<usize>::new());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let callers: Vec<_> = (0..50)
+        .map(|_| {
+            let flight = flight.clone();
+            let calls = calls.clone();
+            tokio::spawn(async move {
+                flight
+                    .run(|| async {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        calls.fetch_add(1, Ordering::SeqCst) + 1
+                    })
+                    .await
+            })
+        })
+        .collect();
+
+    for caller in callers {
+        assert_eq!(caller.await.unwrap(), 1);
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn later_callers_start_a_new_call() {
+    let flight = SingleFlight::<u32>::new();
+
+    assert_eq!(flight.run(|| async { 1 }).await, 1);
+    assert_eq!(flight.run(|| async { 2 }).await, 2);
+}
+
+#[tokio::test]
+async fn cancelled_call_is_retried_by_a_waiter() {
+    let flight = Arc::new(SingleFlight::<&'static str>::new());
+
+    let leader = {
+        let flight = flight.clone();
+        tokio::spawn(async move {
+            flight
+                .run(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "leader"
+                })
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let waiter = {
+        let flight = flight.clone();
+        tokio::spawn(async move { flight.run(|| async { "waiter" }).await })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    leader.abort();
+    assert_eq!(waiter.await.unwrap(), "waiter");
+}
@@ -0,0 +1,96 @@
+//! Quest sources other than Discord. No database needed.
+
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use axum::{routing::get, Json, Router};
+use kythia_quest_api::{
+    sources::{DirectorySource, MirrorSource, QuestSource},
+    utils::discord::build_http_client,
+};
+use serde_json::{json, Value};
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn ids(body: &Value, key: &str) -> Vec<String> {
+    body[key]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn directory_combines_files_with_later_files_winning() {
+    let dir = scratch_dir("quest-source-directory");
+    std::fs::write(
+        dir.join("2025-12-01.json"),
+        json!({
+            "quests": [{ "id": "1", "rev": "old" }, { "id": "2" }],
+            "excluded_quests": [{ "id": "9", "replacement_id": null }]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    // Bare arrays of quests are accepted too
+    std::fs::write(
+        dir.join("2025-12-02.json"),
+        json!([{ "id": "1", "rev": "new" }, { "id": "3" }]).to_string(),
+    )
+    .unwrap();
+    std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+    let response = DirectorySource::new(&dir).fetch().await.unwrap();
+
+    assert_eq!(response.status, 200);
+    assert_eq!(ids(&response.body, "quests"), ["1", "2", "3"]);
+    assert_eq!(response.body["quests"][0]["rev"], "new");
+    assert_eq!(ids(&response.body, "excluded_quests"), ["9"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn empty_directory_is_an_error() {
+    let dir = scratch_dir("quest-source-empty");
+
+    assert!(DirectorySource::new(&dir).fetch().await.is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn mirror_strips_primary_annotations() {
+    let primary = Router::new().route(
+        "/v1/quests",
+        get(|| async {
+            Json(json!({
+                "quests": [
+                    { "id": "1", "config": {}, "_annotations": { "excluded": true, "replaced_by": "2" } },
+                    { "id": "2", "config": {}, "_annotations": { "excluded": false, "replaced_by": null } }
+                ],
+                "excluded_quests": [{ "id": "1", "replacement_id": "2" }]
+            }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, primary).await.unwrap() });
+
+    let client = build_http_client(Duration::from_secs(1), Duration::from_secs(1)).unwrap();
+    let source = MirrorSource::new(client, &format!("http://{}/", addr));
+    let response = source.fetch().await.unwrap();
+
+    assert_eq!(response.status, 200);
+    for quest in response.body["quests"].as_array().unwrap() {
+        assert!(quest.get("_annotations").is_none());
+    }
+    assert_eq!(ids(&response.body, "excluded_quests"), ["1"]);
+}
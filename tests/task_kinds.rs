@@ -0,0 +1,100 @@
+//! Typed task kinds and the task explanations served with each quest. No
+//! database needed.
+
+use std::time::Duration;
+
+use kythia_quest_api::{
+    mock_discord::default_payload,
+    utils::{
+        quest_parser::parse_single_quest,
+        task_kind::{format_duration, TaskKind, TaskSummary, TaskVideo},
+    },
+};
+
+#[test]
+fn kinds_round_trip_through_their_names() {
+    for name in [
+        "PLAY_ON_DESKTOP",
+        "STREAM_ON_DESKTOP",
+        "WATCH_VIDEO",
+        "WATCH_VIDEO_ON_MOBILE",
+        "PLAY_ACTIVITY",
+        "ACHIEVEMENT_IN_ACTIVITY",
+    ] {
+        assert_eq!(TaskKind::from(name).as_str(), name);
+    }
+
+    assert_eq!(
+        TaskKind::from("ACHIEVEMENT_IN_ACTIVITY"),
+        TaskKind::Unknown("ACHIEVEMENT_IN_ACTIVITY".to_string())
+    );
+}
+
+#[test]
+fn targets_of_known_kinds_are_durations() {
+    assert_eq!(
+        TaskKind::PlayOnDesktop.target_duration(900),
+        Some(Duration::from_secs(900))
+    );
+    assert_eq!(
+        TaskKind::Unknown("SOMETHING_NEW".to_string()).target_duration(900),
+        None
+    );
+
+    assert_eq!(format_duration(Duration::from_secs(900)), "15 minutes");
+    assert_eq!(format_duration(Duration::from_secs(30)), "30 seconds");
+    assert_eq!(
+        format_duration(Duration::from_secs(5430)),
+        "1 hour 30 minutes 30 seconds"
+    );
+    assert_eq!(format_duration(Duration::ZERO), "0 seconds");
+}
+
+#[test]
+fn video_tasks_keep_their_metadata() {
+    let payload = default_payload();
+    let (quest, unknown_fields) = parse_single_quest(&payload["quests"][0]).unwrap();
+
+    // Modelled now, so no longer reported as schema drift
+    assert!(!unknown_fields
+        .iter()
+        .any(|field| field.path.starts_with("config.task_config_v2.tasks")));
+
+    let task = &quest.tasks[0];
+    assert_eq!(
+        TaskKind::from(task.task_type.as_str()),
+        TaskKind::WatchVideo
+    );
+    assert_eq!(
+        TaskVideo::from_task(task),
+        Some(TaskVideo {
+            url: Some("quests/1412491570820812933/video.mp4".to_string()),
+            width: Some(1280),
+            height: Some(720),
+            thumbnail: Some("quests/1412491570820812933/video_thumbnail.png".to_string()),
+            title: Some("Watch the Stellar Drift trailer".to_string()),
+        })
+    );
+
+    let summary = TaskSummary::from_task(task);
+    assert_eq!(summary.kind, "WATCH_VIDEO");
+    assert!(summary.known);
+    assert_eq!(summary.duration_seconds, Some(900));
+    assert_eq!(summary.description, "Watch the quest video for 15 minutes");
+}
+
+#[test]
+fn unknown_kinds_are_described_without_a_duration() {
+    let payload = default_payload();
+    let (mut quest, _) = parse_single_quest(&payload["quests"][0]).unwrap();
+    quest.tasks[0].task_type = "ACHIEVEMENT_IN_ACTIVITY".to_string();
+
+    let summary = TaskSummary::from_task(&quest.tasks[0]);
+
+    assert!(!summary.known);
+    assert_eq!(summary.duration_seconds, None);
+    assert_eq!(
+        summary.description,
+        "Complete the ACHIEVEMENT_IN_ACTIVITY task"
+    );
+}
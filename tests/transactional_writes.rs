@@ -0,0 +1,138 @@
+//! Concurrency test for quest saves. Needs a MySQL database, so it is skipped
+//! unless `TEST_DATABASE_URL` is set.
+
+use kythia_quest_api::db::quest_operations::get_complete_quest_by_id;
+use kythia_quest_api::utils::quest_parser::save_discord_quests_to_db;
+use serde_json::{json, Value};
+use sqlx::MySqlPool;
+
+const QUEST_ID: &str = "9000000000000000008";
+
+fn quest_response(name: &str, task_types: &[&str], reward_count: usize) -> Value {
+    let tasks: serde_json::Map<String, Value> = task_types
+        .iter()
+        .map(|task_type| {
+            (
+                task_type.to_string(),
+                json!({ "type": task_type, "target": 900 }),
+            )
+        })
+        .collect();
+
+    let rewards: Vec<Value> = (0..reward_count)
+        .map(|i| {
+            json!({
+                "type": 3,
+                "sku_id": format!("{}", 1000 + i),
+                "messages": {
+                    "name": format!("{} reward {}", name, i),
+                    "name_with_article": format!("a {} reward {}", name, i)
+                },
+                "orb_quantity": null
+            })
+        })
+        .collect();
+
+    json!({
+        "quests": [{
+            "id": QUEST_ID,
+            "preview": false,
+            "config": {
+                "id": QUEST_ID,
+                "config_version": 2,
+                "starts_at": "2025-01-01T00:00:00+00:00",
+                "expires_at": "2099-01-01T00:00:00+00:00",
+                "features": [3, 9],
+                "application": { "id": "1", "name": "Test Game", "link": "https://example.com" },
+                "assets": {},
+                "colors": { "primary": "#000000", "secondary": "#ffffff" },
+                "messages": {
+                    "quest_name": name,
+                    "game_title": "Test Game",
+                    "game_publisher": "Test Publisher"
+                },
+                "task_config_v2": { "tasks": tasks, "join_operator": "or" },
+                "rewards_config": {
+                    "assignment_method": 1,
+                    "rewards": rewards,
+                    "rewards_expire_at": null,
+                    "platforms": [0]
+                },
+                "share_policy": "shareable_everywhere"
+            }
+        }],
+        "excluded_quests": []
+    })
+}
+
+#[tokio::test]
+async fn readers_never_see_a_half_written_quest() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("skipping: TEST_DATABASE_URL is not set");
+        return;
+    };
+
+    let pool = MySqlPool::connect(&url).await.unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+    let version_a = quest_response(
+        "Version A",
+        &["WATCH_VIDEO", "PLAY_ON_DESKTOP", "STREAM_ON_DESKTOP"],
+        1,
+    );
+    let version_b = quest_response("Version B", &["PLAY_ACTIVITY"], 3);
+
+    save_discord_quests_to_db(&pool, &version_a).await.unwrap();
+
+    let writer = {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            for i in 0..40 {
+                let response = if i % 2 == 0 { &version_b } else { &version_a };
+                save_discord_quests_to_db(&pool, response).await.unwrap();
+            }
+        })
+    };
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                for _ in 0..200 {
+                    let quest = get_complete_quest_by_id(&pool, QUEST_ID)
+                        .await
+                        .unwrap()
+                        .expect("quest disappeared mid-save");
+
+                    let expected = match quest.quest.quest_name.as_str() {
+                        "Version A" => (3, 1),
+                        "Version B" => (1, 3),
+                        other => panic!("unexpected quest name {other}"),
+                    };
+                    assert_eq!(
+                        (quest.tasks.len(), quest.rewards.len()),
+                        expected,
+                        "read a half-written {}",
+                        quest.quest.quest_name
+                    );
+                    assert_eq!(quest.features.len(), 2);
+                    assert!(quest
+                        .rewards
+                        .iter()
+                        .all(|r| r.reward_name.starts_with(&quest.quest.quest_name)));
+                }
+            })
+        })
+        .collect();
+
+    writer.await.unwrap();
+    for reader in readers {
+        reader.await.unwrap();
+    }
+
+    sqlx::query("DELETE FROM quests WHERE id = ?")
+        .bind(QUEST_ID)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,118 @@
+//! Upstream fetch behaviour against the mock Discord server. No database needed.
+
+use std::{net::SocketAddr, time::Duration};
+
+use kythia_quest_api::{
+    mock_discord::{default_payload, MockDiscord, MockMode},
+    utils::{
+        discord::{build_http_client, ensure_success, fetch_discord_payload, UpstreamResponse},
+        error::ApiError,
+        secret::Secret,
+    },
+};
+use serde_json::Value;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+fn client() -> reqwest::Client {
+    build_http_client(TIMEOUT, TIMEOUT).unwrap()
+}
+
+async fn fetch(mock: &MockDiscord) -> Result<UpstreamResponse, ApiError> {
+    fetch_discord_payload(&client(), &mock.quests_url(), &Secret::from("token")).await
+}
+
+async fn start_mock() -> MockDiscord {
+    MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_fixture_payload() {
+    let mock = start_mock().await;
+
+    let response = fetch(&mock).await.unwrap();
+
+    assert_eq!(response.status, 200);
+    assert_eq!(response.body["quests"].as_array().unwrap().len(), 2);
+    assert!(ensure_success(&response).is_ok());
+    assert_eq!(mock.request_count(), 1);
+}
+
+#[tokio::test]
+async fn unauthorized_is_returned_then_rejected() {
+    let mock = start_mock().await;
+    mock.set_mode(MockMode::Unauthorized);
+
+    let response = fetch(&mock).await.unwrap();
+
+    assert_eq!(response.status, 401);
+    let error = ensure_success(&response).unwrap_err().to_string();
+    assert!(error.contains("401 Unauthorized"), "{error}");
+}
+
+#[tokio::test]
+async fn rate_limit_keeps_json_body() {
+    let mock = start_mock().await;
+    mock.set_mode(MockMode::RateLimited {
+        retry_after_secs: 7,
+    });
+
+    let response = fetch(&mock).await.unwrap();
+
+    assert_eq!(response.status, 429);
+    assert_eq!(response.body["retry_after"], 7.0);
+    assert!(ensure_success(&response).is_err());
+}
+
+#[tokio::test]
+async fn server_error_keeps_text_body() {
+    let mock = start_mock().await;
+    mock.set_mode(MockMode::ServerError(503));
+
+    let response = fetch(&mock).await.unwrap();
+
+    assert_eq!(response.status, 503);
+    assert!(matches!(response.body, Value::String(_)));
+    let error = ensure_success(&response).unwrap_err().to_string();
+    assert!(error.contains("upstream connect error"), "{error}");
+}
+
+#[tokio::test]
+async fn hung_upstream_times_out() {
+    let mock = start_mock().await;
+    mock.set_mode(MockMode::Timeout);
+
+    let result = fetch(&mock).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn slow_upstream_within_timeout_succeeds() {
+    let mock = start_mock().await;
+    mock.set_mode(MockMode::Slow(Duration::from_millis(300)));
+
+    let response = fetch(&mock).await.unwrap();
+
+    assert_eq!(response.status, 200);
+}
+
+#[test]
+fn parses_mock_modes() {
+    assert_eq!("ok".parse::<MockMode>(), Ok(MockMode::Ok));
+    assert_eq!("401".parse::<MockMode>(), Ok(MockMode::Unauthorized));
+    assert_eq!(
+        "429:30".parse::<MockMode>(),
+        Ok(MockMode::RateLimited {
+            retry_after_secs: 30
+        })
+    );
+    assert_eq!("502".parse::<MockMode>(), Ok(MockMode::ServerError(502)));
+    assert_eq!(
+        "slow:250".parse::<MockMode>(),
+        Ok(MockMode::Slow(Duration::from_millis(250)))
+    );
+    assert!("404".parse::<MockMode>().is_err());
+}
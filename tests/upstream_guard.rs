@@ -0,0 +1,156 @@
+//! Retries, rate limit pauses and the circuit breaker, against the mock
+//! Discord server. No database needed.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use kythia_quest_api::{
+    mock_discord::{default_payload, MockDiscord, MockMode},
+    sources::DiscordSource,
+    utils::{
+        credentials::{TokenProvider, TokenSource},
+        discord::build_http_client,
+        upstream_guard::{CircuitState, UpstreamGuard, UpstreamPolicy},
+    },
+};
+
+async fn start() -> (MockDiscord, DiscordSource) {
+    let mock = MockDiscord::start(SocketAddr::from(([127, 0, 0, 1], 0)), default_payload())
+        .await
+        .unwrap();
+    let client = build_http_client(Duration::from_secs(1), Duration::from_secs(1)).unwrap();
+    let source = DiscordSource::new(client, &mock.quests_url(), token());
+    (mock, source)
+}
+
+fn token() -> Arc<TokenProvider> {
+    Arc::new(TokenProvider::new("token", TokenSource::Environment))
+}
+
+fn policy(max_retries: u32, breaker_threshold: u32) -> UpstreamPolicy {
+    UpstreamPolicy {
+        max_retries,
+        retry_backoff: Duration::from_millis(10),
+        breaker_threshold,
+        breaker_cooldown: Duration::from_secs(1),
+        max_rate_limit_pause: Duration::from_secs(5),
+    }
+}
+
+#[tokio::test]
+async fn server_errors_are_retried_with_backoff() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(2, 100));
+    mock.set_mode(MockMode::ServerError(503));
+
+    let response = guard.fetch(&source).await.unwrap();
+
+    assert_eq!(response.status, 503);
+    assert_eq!(mock.request_count(), 3);
+    assert_eq!(guard.status().consecutive_failures, 1);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(2, 100));
+    mock.set_mode(MockMode::Unauthorized);
+
+    let response = guard.fetch(&source).await.unwrap();
+
+    assert_eq!(response.status, 401);
+    assert_eq!(mock.request_count(), 1);
+}
+
+#[tokio::test]
+async fn network_errors_are_retried() {
+    // Nothing listens on a port we bound and released
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client = build_http_client(Duration::from_secs(1), Duration::from_secs(1)).unwrap();
+    let source = DiscordSource::new(client, &format!("http://{}/quests", addr), token());
+    let guard = UpstreamGuard::new(policy(2, 100));
+
+    assert!(guard.fetch(&source).await.is_err());
+    assert_eq!(guard.status().consecutive_failures, 1);
+}
+
+#[tokio::test]
+async fn rate_limit_pauses_every_fetch_until_retry_after() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(2, 100));
+    mock.set_mode(MockMode::RateLimited {
+        retry_after_secs: 1,
+    });
+
+    let response = guard.fetch(&source).await.unwrap();
+    assert_eq!(response.status, 429);
+    assert_eq!(response.retry_after, Some(Duration::from_secs(1)));
+    assert!(guard.status().rate_limited_until.is_some());
+
+    // Paused: upstream is not called at all
+    mock.set_mode(MockMode::Ok);
+    assert!(guard.fetch(&source).await.is_err());
+    assert_eq!(mock.request_count(), 1);
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(guard.fetch(&source).await.unwrap().status, 200);
+    assert_eq!(mock.request_count(), 2);
+}
+
+#[tokio::test]
+async fn huge_retry_after_is_clamped() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(2, 100));
+    mock.set_mode(MockMode::RateLimited {
+        retry_after_secs: u64::MAX,
+    });
+
+    let response = guard.fetch(&source).await.unwrap();
+    assert_eq!(response.status, 429);
+    assert_eq!(response.retry_after, Some(Duration::MAX));
+
+    let until = guard.status().rate_limited_until.unwrap();
+    assert!(until <= chrono::Utc::now() + chrono::Duration::seconds(5));
+}
+
+#[tokio::test]
+async fn circuit_opens_after_repeated_failures_and_recovers() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(0, 2));
+    mock.set_mode(MockMode::ServerError(500));
+
+    guard.fetch(&source).await.unwrap();
+    assert_eq!(guard.status().circuit, CircuitState::Closed);
+    guard.fetch(&source).await.unwrap();
+    assert_eq!(guard.status().circuit, CircuitState::Open);
+
+    // Open: upstream is not called
+    assert!(guard.fetch(&source).await.is_err());
+    assert_eq!(mock.request_count(), 2);
+
+    // After the cooldown one trial is let through, and success closes it
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(guard.status().circuit, CircuitState::HalfOpen);
+    mock.set_mode(MockMode::Ok);
+    assert_eq!(guard.fetch(&source).await.unwrap().status, 200);
+
+    let status = guard.status();
+    assert_eq!(status.circuit, CircuitState::Closed);
+    assert_eq!(status.consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn failed_half_open_trial_reopens_the_circuit() {
+    let (mock, source) = start().await;
+    let guard = UpstreamGuard::new(policy(0, 1));
+    mock.set_mode(MockMode::ServerError(502));
+
+    guard.fetch(&source).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    guard.fetch(&source).await.unwrap();
+
+    assert_eq!(guard.status().circuit, CircuitState::Open);
+    assert_eq!(mock.request_count(), 2);
+}